@@ -64,7 +64,7 @@ fn main() -> ! {
         1000,
     );
 
-    let mut disp: GraphicsMode<_> = Builder::new().connect_i2c(i2c).into();
+    let mut disp: GraphicsMode<_> = Builder::new().connect_i2c(i2c).unwrap().into();
 
     disp.init().unwrap();
     disp.flush().unwrap();
@@ -96,7 +96,9 @@ fn main() -> ! {
 
     disp.flush().unwrap();
 
-    loop {}
+    loop {
+        cortex_m::asm::wfi();
+    }
 }
 
 #[exception]
@@ -54,9 +54,10 @@ fn main() -> ! {
         1000,
     );
 
-    let mut disp: GraphicsMode<_> = Builder::new()
-        .with_size(DisplaySize::Display128x32)
+    let mut disp: GraphicsMode<_, _> = Builder::new()
+        .with_size(DisplaySize128x32)
         .connect_i2c(i2c)
+        .unwrap()
         .into();
     disp.init().unwrap();
     disp.flush().unwrap();
@@ -93,7 +94,9 @@ fn main() -> ! {
 
     disp.flush().unwrap();
 
-    loop {}
+    loop {
+        cortex_m::asm::wfi();
+    }
 }
 
 #[exception]
@@ -71,7 +71,7 @@ fn main() -> ! {
         1000,
     );
 
-    let mut disp: GraphicsMode<_> = Builder::new().connect_i2c(i2c).into();
+    let mut disp: GraphicsMode<_> = Builder::new().connect_i2c(i2c).unwrap().into();
 
     disp.init().unwrap();
     disp.flush().unwrap();
@@ -82,7 +82,9 @@ fn main() -> ! {
 
     disp.flush().unwrap();
 
-    loop {}
+    loop {
+        cortex_m::asm::wfi();
+    }
 }
 
 #[exception]
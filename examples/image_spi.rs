@@ -65,11 +65,12 @@ fn main() -> ! {
         &mut rcc.apb2,
     );
 
-    let mut disp: GraphicsMode<_> = Builder::new().connect_spi(spi, dc, cs).into();
+    let mut disp: GraphicsMode<_> = Builder::new().connect_spi(spi, dc, cs).unwrap().into();
 
     // If you aren't using the Chip Select pin, use this instead:
     // let mut disp: GraphicsMode<_> = Builder::new()
     //     .connect_spi(spi, dc, sh1107::builder::NoOutputPin::new())
+    //     .unwrap()
     //     .into();
 
     disp.init().unwrap();
@@ -81,7 +82,9 @@ fn main() -> ! {
 
     disp.flush().unwrap();
 
-    loop {}
+    loop {
+        cortex_m::asm::wfi();
+    }
 }
 
 #[exception]
@@ -73,6 +73,7 @@ fn main() -> ! {
         // Set initial rotation at 90 degrees clockwise
         .with_rotation(DisplayRotation::Rotate90)
         .connect_i2c(i2c)
+        .unwrap()
         .into();
 
     disp.init().unwrap();
@@ -95,7 +96,9 @@ fn main() -> ! {
 
     disp.flush().unwrap();
 
-    loop {}
+    loop {
+        cortex_m::asm::wfi();
+    }
 }
 
 #[exception]
@@ -14,7 +14,7 @@
 //! let spi = /* SPI interface from your HAL of choice */;
 //! let dc = /* GPIO data/command select pin */;
 //!
-//! Builder::new().connect_spi(spi, dc);
+//! Builder::new().connect_spi(spi, dc).unwrap();
 //! ```
 //!
 //! Connect over I2C, changing lots of options
@@ -25,10 +25,25 @@
 //! Builder::new()
 //!     .with_rotation(DisplayRotation::Rotate180)
 //!     .with_i2c_addr(0x3D)
-//!     .with_size(DisplaySize::Display128x32)
-//!     .connect_i2c(i2c);
+//!     .with_size(DisplaySize128x32)
+//!     .connect_i2c(i2c)
+//!     .unwrap();
 //! ```
 //!
+//! Presets are available for common modules, which set the size, rotation and panel settings
+//! that suit them:
+//!
+//! ```rust,ignore
+//! let display: GraphicsMode<_, _> = Builder::adafruit_featherwing_128x64()
+//!     .connect_i2c(i2c)
+//!     .unwrap()
+//!     .into();
+//! ```
+//!
+//! The `connect` methods check the configuration and return a
+//! [`ConfigError`](../config/enum.ConfigError.html) if it can't work, e.g. for an I2C address
+//! other than 0x3C or 0x3D or a panel config value outside of the datasheet range.
+//!
 //! The above examples will produce a [RawMode](../mode/raw/struct.RawMode.html) instance
 //! by default. You need to coerce them into a mode by specifying a type on assignment. For
 //! example, to use [`GraphicsMode` mode](../mode/graphics/struct.GraphicsMode.html):
@@ -37,26 +52,55 @@
 //! let spi = /* SPI interface from your HAL of choice */;
 //! let dc = /* GPIO data/command select pin */;
 //!
-//! let display: GraphicsMode<_> = Builder::new().connect_spi(spi, dc).into();
+//! let display: GraphicsMode<_> = Builder::new().connect_spi(spi, dc).unwrap().into();
+//! ```
+//!
+//! The display size is part of the mode's type, so a non-default size must also be named (or
+//! inferred) in the mode type:
+//!
+//! ```rust,ignore
+//! let display: GraphicsMode<_, DisplaySize128x128> = Builder::new()
+//!     .with_size(DisplaySize128x128)
+//!     .connect_i2c(i2c)
+//!     .unwrap()
+//!     .into();
 //! ```
 
 use core::marker::PhantomData;
-use hal::{self, digital::v2::OutputPin};
+
+#[cfg(feature = "async")]
+use crate::hal::{AsyncI2c, AsyncSpiDevice};
+#[cfg(feature = "display-interface")]
+use crate::interface::WriteOnlyInterface;
 
 use crate::{
+    config::{ConfigError, PanelConfig, VcomhLevel},
     displayrotation::DisplayRotation,
-    displaysize::DisplaySize,
-    interface::{I2cInterface, SpiInterface},
+    displaysize::{
+        DisplaySize, DisplaySize128x128, DisplaySize128x64, DisplaySize64x128, DisplaySizeType,
+    },
+    hal::OutputPin,
+    interface::{
+        i2c::DEFAULT_CHUNK_LEN,
+        parallel::{OutputBus, ParallelProtocol},
+        I2cInterface, ParallelInterface, Spi3WireInterface, SpiInterface,
+    },
     mode::{displaymode::DisplayMode, raw::RawMode},
     properties::DisplayProperties,
 };
 
+/// Number of rows and columns of SH1107 display RAM
+const RAM_SIZE: u16 = 128;
+
 /// Builder struct. Driver options and interface are set using its methods.
 #[derive(Clone, Copy)]
-pub struct Builder {
-    display_size: DisplaySize,
+pub struct Builder<SIZE = DisplaySize128x64> {
+    display_size: SIZE,
     rotation: DisplayRotation,
     i2c_addr: u8,
+    i2c_chunk_len: usize,
+    parallel_protocol: ParallelProtocol,
+    panel_config: Option<PanelConfig>,
 }
 
 impl Default for Builder {
@@ -69,69 +113,341 @@ impl Builder {
     /// Create new builder with a default size of 128 x 64 pixels and no rotation.
     pub fn new() -> Builder {
         Builder {
-            display_size: DisplaySize::Display128x64,
+            display_size: DisplaySize128x64,
             rotation: DisplayRotation::Rotate0,
             i2c_addr: 0x3c,
+            i2c_chunk_len: DEFAULT_CHUNK_LEN,
+            parallel_protocol: ParallelProtocol::I8080,
+            panel_config: None,
+        }
+    }
+
+    /// Adafruit 128x64 OLED FeatherWing (product 4650). The 64x128 panel is mounted sideways, so
+    /// it is rotated to landscape, and is set up like Adafruit's SH110X driver does.
+    pub fn adafruit_featherwing_128x64() -> Builder<DisplaySize64x128> {
+        let panel_config = PanelConfig {
+            oscillator_frequency: 0x5,
+            clock_divide: 2,
+            contrast: 0x4F,
+            precharge_period: 0x2,
+            discharge_period: 0x2,
+            vcomh_level: VcomhLevel::Raw(0x35),
+            dc_dc: false,
+            ..PanelConfig::for_size(DisplaySize::Display64x128)
+        };
+
+        Builder {
+            panel_config: Some(panel_config),
+            ..Self::new()
+                .with_size(DisplaySize64x128)
+                .with_rotation(DisplayRotation::Rotate90)
+        }
+    }
+
+    /// Pimoroni 1.12" 128x128 mono OLED breakout, whose panel runs from the VPP regulator on the
+    /// breakout.
+    pub fn pimoroni_128x128() -> Builder<DisplaySize128x128> {
+        let panel_config = PanelConfig {
+            oscillator_frequency: 0x5,
+            clock_divide: 2,
+            precharge_period: 0x2,
+            discharge_period: 0x2,
+            vcomh_level: VcomhLevel::Raw(0x35),
+            dc_dc: false,
+            ..PanelConfig::for_size(DisplaySize::Display128x128)
+        };
+
+        Builder {
+            panel_config: Some(panel_config),
+            ..Self::new().with_size(DisplaySize128x128)
+        }
+    }
+
+    /// Waveshare 1.5" 128x128 OLED module (B). Uses the init settings of Waveshare's demo code,
+    /// which runs the panel from the module's VPP supply with a slower clock and lower contrast.
+    pub fn waveshare_128x128() -> Builder<DisplaySize128x128> {
+        let panel_config = PanelConfig {
+            oscillator_frequency: 0x4,
+            clock_divide: 2,
+            contrast: 0x6F,
+            precharge_period: 0x2,
+            discharge_period: 0x2,
+            vcomh_level: VcomhLevel::Raw(0x35),
+            dc_dc: false,
+            ..PanelConfig::for_size(DisplaySize::Display128x128)
+        };
+
+        Builder {
+            panel_config: Some(panel_config),
+            ..Self::new().with_size(DisplaySize128x128)
+        }
+    }
+
+    /// Seeed 64x128 SH1107 OLED display, used in portrait orientation. Seeed's SH1107G glass
+    /// expects the SH1106 COM pin command with the alternative configuration, and a low contrast.
+    pub fn seeed_64x128() -> Builder<DisplaySize64x128> {
+        let panel_config = PanelConfig {
+            oscillator_frequency: 0x5,
+            clock_divide: 1,
+            contrast: 0x2F,
+            precharge_period: 0x2,
+            discharge_period: 0x2,
+            vcomh_level: VcomhLevel::Raw(0x35),
+            alternative_com_pins: Some(true),
+            ..PanelConfig::for_size(DisplaySize::Display64x128)
+        };
+
+        Builder {
+            panel_config: Some(panel_config),
+            ..Self::new().with_size(DisplaySize64x128)
         }
     }
 }
 
-impl Builder {
-    /// Set the size of the display. Supported sizes are defined by [DisplaySize].
-    pub fn with_size(self, display_size: DisplaySize) -> Self {
-        Self {
+impl<SIZE> Builder<SIZE>
+where
+    SIZE: DisplaySizeType,
+{
+    /// Set the size of the display. Supported sizes are the `DisplaySize*` types implementing
+    /// [DisplaySizeType], e.g. `DisplaySize128x32`. Types for other panels can be defined with
+    /// [`custom_display_size!`](../macro.custom_display_size.html).
+    pub fn with_size<NSIZE>(self, display_size: NSIZE) -> Builder<NSIZE>
+    where
+        NSIZE: DisplaySizeType,
+    {
+        Builder {
             display_size,
-            ..self
+            rotation: self.rotation,
+            i2c_addr: self.i2c_addr,
+            i2c_chunk_len: self.i2c_chunk_len,
+            parallel_protocol: self.parallel_protocol,
+            panel_config: self.panel_config,
         }
     }
 
     /// Set the I2C address to use. Defaults to 0x3C which is the most common address.
-    /// The other address specified in the datasheet is 0x3D. Ignored when using SPI interface.
+    /// The other address specified in the datasheet is 0x3D, any other address is rejected by
+    /// `connect_i2c`. Ignored when using SPI interface.
     pub fn with_i2c_addr(self, i2c_addr: u8) -> Self {
         Self { i2c_addr, ..self }
     }
 
+    /// Set the number of data bytes sent per I2C transaction. Defaults to 64 and must be in
+    /// `1..=128`. Ignored when using SPI interface.
+    pub fn with_i2c_chunk_len(self, i2c_chunk_len: usize) -> Self {
+        Self {
+            i2c_chunk_len,
+            ..self
+        }
+    }
+
+    /// Set the protocol used by a parallel interface. Defaults to
+    /// [`ParallelProtocol::I8080`](../interface/parallel/enum.ParallelProtocol.html). Ignored when
+    /// using other interfaces.
+    pub fn with_parallel_protocol(self, parallel_protocol: ParallelProtocol) -> Self {
+        Self {
+            parallel_protocol,
+            ..self
+        }
+    }
+
     /// Set the rotation of the display to one of four values. Defaults to no rotation.
     pub fn with_rotation(self, rotation: DisplayRotation) -> Self {
         Self { rotation, ..self }
     }
 
+    /// Set the analog and timing settings sent to the panel by `init`. Defaults to
+    /// [`PanelConfig::for_size`](../config/struct.PanelConfig.html#method.for_size) for the
+    /// display size.
+    pub fn with_panel_config(self, panel_config: PanelConfig) -> Self {
+        Self {
+            panel_config: Some(panel_config),
+            ..self
+        }
+    }
+
+    /// Finish the builder and use a transport from the `display-interface` crate to communicate
+    /// with the display, e.g. one from `display-interface-spi` or
+    /// `display-interface-parallel-gpio`. With the `async` feature, `AsyncWriteOnlyDataCommand`
+    /// transports are accepted as well.
+    #[cfg(feature = "display-interface")]
+    pub fn connect<DI>(
+        self,
+        iface: DI,
+    ) -> Result<DisplayMode<RawMode<WriteOnlyInterface<DI>, SIZE>>, ConfigError> {
+        let properties = self.properties(WriteOnlyInterface::new(iface))?;
+        Ok(DisplayMode::<RawMode<WriteOnlyInterface<DI>, SIZE>>::new(
+            properties,
+        ))
+    }
+
     /// Finish the builder and use I2C to communicate with the display
-    pub fn connect_i2c<I2C, CommE>(self, i2c: I2C) -> DisplayMode<RawMode<I2cInterface<I2C>>>
+    ///
+    /// With the `eh1` feature, an embedded-hal 1.0 bus can be used by wrapping it in
+    /// [`Eh1`](../eh1/struct.Eh1.html).
+    pub fn connect_i2c<I2C>(
+        self,
+        i2c: I2C,
+    ) -> Result<DisplayMode<RawMode<I2cInterface<I2C>, SIZE>>, ConfigError> {
+        let properties = self.i2c_properties(i2c)?;
+        Ok(DisplayMode::<RawMode<I2cInterface<I2C>, SIZE>>::new(
+            properties,
+        ))
+    }
+
+    /// Finish the builder and use SPI to communicate with the display
+    ///
+    /// If the Chip Select (CS) pin is not required, [`NoOutputPin`] can be used as a dummy argument.
+    /// With the `eh1` feature, an embedded-hal 1.0 `SpiDevice` can be used by wrapping it in
+    /// [`Eh1`](../eh1/struct.Eh1.html). The device drives its own chip select, so pass
+    /// [`NoOutputPin`] as `cs`.
+    ///
+    /// [`NoOutputPin`]: ./struct.NoOutputPin.html
+    #[allow(clippy::type_complexity)]
+    pub fn connect_spi<SPI, DC, CS>(
+        self,
+        spi: SPI,
+        dc: DC,
+        cs: CS,
+    ) -> Result<DisplayMode<RawMode<SpiInterface<SPI, DC, CS>, SIZE>>, ConfigError> {
+        let properties = self.properties(SpiInterface::new(spi, dc, cs))?;
+        Ok(DisplayMode::<RawMode<SpiInterface<SPI, DC, CS>, SIZE>>::new(properties))
+    }
+
+    /// Finish the builder and use an asynchronous I2C bus to communicate with the display
+    #[cfg(feature = "async")]
+    pub fn connect_i2c_async<I2C, CommE>(
+        self,
+        i2c: I2C,
+    ) -> Result<DisplayMode<RawMode<I2cInterface<I2C>, SIZE>>, ConfigError>
     where
-        I2C: hal::blocking::i2c::Write<Error = CommE>,
+        I2C: AsyncI2c<Error = CommE>,
     {
-        let properties = DisplayProperties::new(
-            I2cInterface::new(i2c, self.i2c_addr),
-            self.display_size,
-            self.rotation,
-        );
-        DisplayMode::<RawMode<I2cInterface<I2C>>>::new(properties)
+        let properties = self.i2c_properties(i2c)?;
+        Ok(DisplayMode::<RawMode<I2cInterface<I2C>, SIZE>>::new(
+            properties,
+        ))
     }
 
-    /// Finish the builder and use SPI to communicate with the display
+    /// Finish the builder and use an asynchronous SPI device to communicate with the display.
+    /// Chip select is driven by the device.
+    #[cfg(feature = "async")]
+    #[allow(clippy::type_complexity)]
+    pub fn connect_spi_async<SPI, DC, PinE>(
+        self,
+        spi: SPI,
+        dc: DC,
+    ) -> Result<DisplayMode<RawMode<SpiInterface<SPI, DC, NoOutputPin<PinE>>, SIZE>>, ConfigError>
+    where
+        SPI: AsyncSpiDevice,
+        DC: OutputPin<Error = PinE>,
+    {
+        let properties = self.properties(SpiInterface::new(spi, dc, NoOutputPin::new()))?;
+        Ok(DisplayMode::<
+            RawMode<SpiInterface<SPI, DC, NoOutputPin<PinE>>, SIZE>,
+        >::new(properties))
+    }
+
+    /// Finish the builder and use 3-wire SPI to communicate with the display. The D/C flag is
+    /// sent as the first bit of 9-bit words, so no D/C pin is needed.
     ///
-    /// If the Chip Select (CS) pin is not required, [`NoOutputPin`] can be used as a dummy argument
+    /// If the Chip Select (CS) pin is not required, [`NoOutputPin`] can be used as a dummy argument.
+    /// An embedded-hal 1.0 `SpiDevice` can be used as for [`connect_spi`](#method.connect_spi).
     ///
     /// [`NoOutputPin`]: ./struct.NoOutputPin.html
-    pub fn connect_spi<SPI, DC, CS, CommE, PinE>(
+    #[allow(clippy::type_complexity)]
+    pub fn connect_spi_3wire<SPI, CS>(
         self,
         spi: SPI,
+        cs: CS,
+    ) -> Result<DisplayMode<RawMode<Spi3WireInterface<SPI, CS>, SIZE>>, ConfigError> {
+        let properties = self.properties(Spi3WireInterface::new(spi, cs))?;
+        Ok(DisplayMode::<RawMode<Spi3WireInterface<SPI, CS>, SIZE>>::new(properties))
+    }
+
+    /// Finish the builder and use an 8-bit parallel bus to communicate with the display
+    ///
+    /// `wr` and `rd` are the `WR` and `RD` pins for the 8080 protocol, or the `R/W` and `E` pins
+    /// for the 6800 protocol. If the Chip Select (CS) pin is not required, [`NoOutputPin`] can be
+    /// used as a dummy argument
+    ///
+    /// [`NoOutputPin`]: ./struct.NoOutputPin.html
+    #[allow(clippy::type_complexity)]
+    pub fn connect_parallel<BUS, DC, WR, RD, CS, CommE, PinE>(
+        self,
+        bus: BUS,
         dc: DC,
+        wr: WR,
+        rd: RD,
         cs: CS,
-    ) -> DisplayMode<RawMode<SpiInterface<SPI, DC, CS>>>
+    ) -> Result<DisplayMode<RawMode<ParallelInterface<BUS, DC, WR, RD, CS>, SIZE>>, ConfigError>
     where
-        SPI: hal::blocking::spi::Transfer<u8, Error = CommE>
-            + hal::blocking::spi::Write<u8, Error = CommE>,
+        BUS: OutputBus<Error = CommE>,
         DC: OutputPin<Error = PinE>,
+        WR: OutputPin<Error = PinE>,
+        RD: OutputPin<Error = PinE>,
         CS: OutputPin<Error = PinE>,
     {
-        let properties = DisplayProperties::new(
-            SpiInterface::new(spi, dc, cs),
-            self.display_size,
-            self.rotation,
-        );
-        DisplayMode::<RawMode<SpiInterface<SPI, DC, CS>>>::new(properties)
+        let properties = self.properties(ParallelInterface::new(
+            bus,
+            dc,
+            wr,
+            rd,
+            cs,
+            self.parallel_protocol,
+        ))?;
+        Ok(DisplayMode::<
+            RawMode<ParallelInterface<BUS, DC, WR, RD, CS>, SIZE>,
+        >::new(properties))
+    }
+
+    /// Display properties for the given interface with all options applied
+    fn properties<DI>(&self, iface: DI) -> Result<DisplayProperties<DI, SIZE>, ConfigError> {
+        self.validate()?;
+        let properties = DisplayProperties::new(iface, self.display_size, self.rotation);
+
+        Ok(match self.panel_config {
+            Some(config) => properties.with_panel_config(config),
+            None => properties,
+        })
+    }
+
+    /// Display properties for an I2C interface with all options applied, for blocking and
+    /// asynchronous buses alike
+    fn i2c_properties<I2C>(
+        &self,
+        i2c: I2C,
+    ) -> Result<DisplayProperties<I2cInterface<I2C>, SIZE>, ConfigError> {
+        self.validate_i2c()?;
+        self.properties(I2cInterface::new(i2c, self.i2c_addr).with_chunk_len(self.i2c_chunk_len)?)
+    }
+
+    /// Check the options used by every interface
+    fn validate(&self) -> Result<(), ConfigError> {
+        // Every size has to fit into display RAM, including its offsets
+        let (width, height) = SIZE::SIZE.dimensions();
+        if width as u16 + SIZE::SIZE.column_offset() as u16 > RAM_SIZE
+            || height as u16 > RAM_SIZE
+            || height % 8 != 0
+            || SIZE::SIZE.row_offset() as u16 >= RAM_SIZE
+        {
+            return Err(ConfigError::DisplaySize);
+        }
+
+        match self.panel_config {
+            Some(config) => config.validate(),
+            None => Ok(()),
+        }
+    }
+
+    /// Check the options only used by I2C interfaces. The chunk length is checked by
+    /// `I2cInterface::with_chunk_len`.
+    fn validate_i2c(&self) -> Result<(), ConfigError> {
+        if !(0x3C..=0x3D).contains(&self.i2c_addr) {
+            return Err(ConfigError::I2cAddress);
+        }
+
+        Ok(())
     }
 }
 
@@ -148,6 +464,12 @@ impl<PinE> NoOutputPin<PinE> {
     }
 }
 
+impl<PinE> Default for NoOutputPin<PinE> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<PinE> OutputPin for NoOutputPin<PinE> {
     type Error = PinE;
     fn set_low(&mut self) -> Result<(), PinE> {
@@ -160,9 +482,19 @@ impl<PinE> OutputPin for NoOutputPin<PinE> {
 
 #[cfg(test)]
 mod tests {
-    use super::NoOutputPin;
-    use embedded_hal::digital::v2::OutputPin;
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::{Builder, NoOutputPin};
+    use crate::{
+        config::{ConfigError, PanelConfig},
+        displaysize::{DisplaySize, DisplaySizeType},
+        hal::OutputPin,
+        interface::mock::MockInterface,
+    };
 
+    #[derive(Debug)]
     enum SomeError {}
 
     struct SomeDriver<P: OutputPin<Error = SomeError>> {
@@ -171,10 +503,168 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_output_pin() {
         let p = NoOutputPin::new();
         let _d = SomeDriver { p };
 
         assert!(true);
     }
+
+    #[test]
+    fn presets_send_module_init_sequences() {
+        fn init_commands<SIZE: DisplaySizeType>(builder: Builder<SIZE>) -> Vec<Vec<u8>> {
+            let mut properties = builder.properties(MockInterface::default()).unwrap();
+            properties.init_column_mode().unwrap();
+            properties.iface().commands()
+        }
+
+        type Commands = &'static [&'static [u8]];
+
+        // Each sequence differs from the others in the clock, display offset, DC-DC converter,
+        // segment remap, COM pin configuration or contrast
+        let presets: [(Vec<Vec<u8>>, Commands); 4] = [
+            (
+                init_commands(Builder::adafruit_featherwing_128x64()),
+                &[
+                    &[0xAE],
+                    &[0xD5, 0x51],
+                    &[0xA8, 0x7F],
+                    &[0xD3, 0x60],
+                    &[0xDC, 0x00],
+                    &[0x20],
+                    &[0xAD, 0x8A],
+                    &[0xA0],
+                    &[0xC8],
+                    &[0x81, 0x4F],
+                    &[0xD9, 0x22],
+                    &[0xDB, 0x35],
+                    &[0xA4],
+                    &[0xA6],
+                    &[0xAF],
+                ],
+            ),
+            (
+                init_commands(Builder::pimoroni_128x128()),
+                &[
+                    &[0xAE],
+                    &[0xD5, 0x51],
+                    &[0xA8, 0x7F],
+                    &[0xD3, 0x00],
+                    &[0xDC, 0x00],
+                    &[0x20],
+                    &[0xAD, 0x8A],
+                    &[0xA1],
+                    &[0xC8],
+                    &[0x81, 0x80],
+                    &[0xD9, 0x22],
+                    &[0xDB, 0x35],
+                    &[0xA4],
+                    &[0xA6],
+                    &[0xAF],
+                ],
+            ),
+            (
+                init_commands(Builder::waveshare_128x128()),
+                &[
+                    &[0xAE],
+                    &[0xD5, 0x41],
+                    &[0xA8, 0x7F],
+                    &[0xD3, 0x00],
+                    &[0xDC, 0x00],
+                    &[0x20],
+                    &[0xAD, 0x8A],
+                    &[0xA1],
+                    &[0xC8],
+                    &[0x81, 0x6F],
+                    &[0xD9, 0x22],
+                    &[0xDB, 0x35],
+                    &[0xA4],
+                    &[0xA6],
+                    &[0xAF],
+                ],
+            ),
+            (
+                init_commands(Builder::seeed_64x128()),
+                &[
+                    &[0xAE],
+                    &[0xD5, 0x50],
+                    &[0xA8, 0x7F],
+                    &[0xD3, 0x60],
+                    &[0xDC, 0x00],
+                    &[0x20],
+                    &[0xAD, 0x8B],
+                    &[0xA1],
+                    &[0xC8],
+                    &[0xDA, 0x12],
+                    &[0x81, 0x2F],
+                    &[0xD9, 0x22],
+                    &[0xDB, 0x35],
+                    &[0xA4],
+                    &[0xA6],
+                    &[0xAF],
+                ],
+            ),
+        ];
+
+        for (commands, expected) in presets {
+            assert_eq!(commands, expected);
+        }
+    }
+
+    #[test]
+    fn invalid_configuration_is_rejected() {
+        assert_eq!(Builder::new().validate_i2c(), Ok(()));
+        assert_eq!(
+            Builder::new().with_i2c_addr(0x3E).validate_i2c(),
+            Err(ConfigError::I2cAddress)
+        );
+        assert_eq!(
+            Builder::new().with_i2c_chunk_len(0).connect_i2c(()).err(),
+            Some(ConfigError::I2cChunkLen)
+        );
+
+        let config = PanelConfig {
+            clock_divide: 0,
+            ..PanelConfig::default()
+        };
+        assert_eq!(
+            Builder::new().with_panel_config(config).validate(),
+            Err(ConfigError::ClockDivide)
+        );
+
+        #[derive(Clone, Copy)]
+        struct TooWide;
+
+        impl DisplaySizeType for TooWide {
+            const SIZE: DisplaySize = DisplaySize::Custom {
+                width: 96,
+                height: 64,
+                column_offset: 64,
+                row_offset: 0,
+            };
+
+            type Buffer = [u8; 96 * 64 / 8];
+
+            fn new_buffer() -> Self::Buffer {
+                [0; 96 * 64 / 8]
+            }
+        }
+
+        assert_eq!(
+            Builder::new().with_size(TooWide).validate(),
+            Err(ConfigError::DisplaySize)
+        );
+
+        for size in [
+            DisplaySize::Display64x128,
+            DisplaySize::Display128x64,
+            DisplaySize::Display128x64NoOffset,
+            DisplaySize::Display128x32,
+            DisplaySize::Display128x128,
+        ] {
+            let (width, _) = size.dimensions();
+            assert!(width + size.column_offset() <= 128);
+        }
+    }
 }
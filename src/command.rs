@@ -1,12 +1,17 @@
-use super::interface::DisplayInterface;
+//! sh1107 Commands
+
+use core::convert::TryFrom;
 
-/// sh1107 Commands
+#[cfg(feature = "async")]
+use super::interface::AsyncDisplayInterface;
+use super::interface::DisplayInterface;
+use crate::DriverError;
 
 /// Commands
 #[derive(Debug)]
 #[allow(dead_code)]
 pub enum Command {
-    /// Set contrast. Higher number is higher contrast. Default = 0x7F
+    /// Set contrast. Higher number is higher contrast. Default = 0x80
     Contrast(u8),
     /// Turn entire display on. If set, all pixels will
     /// be set to on, if not, the value in memory will be used.
@@ -17,58 +22,89 @@ pub enum Command {
     DisplayOn(bool),
     /// Set column address lower 4 bits
     ColumnAddressLow(u8),
-    /// Set column address higher 4 bits
+    /// Set column address higher 3 bits
     ColumnAddressHigh(u8),
     /// Set page address
     PageAddress(Page),
-    /// Set display start line from 0-63
+    /// Set memory addressing mode
+    AddressMode(AddressMode),
+    /// Set display start line from 0-127
     StartLine(u8),
     /// Reverse columns from 127-0
     SegmentRemap(bool),
-    /// Set multipex ratio from 15-63 (MUX-1)
+    /// Set multipex ratio from 0-127 (MUX-1)
     Multiplex(u8),
     /// Scan from COM[n-1] to COM0 (where N is mux ratio)
     ReverseComDir(bool),
-    /// Set vertical shift
+    /// Set vertical shift from 0-127
     DisplayOffset(u8),
     /// Setup com hardware configuration
     /// First value indicates sequential (false) or alternative (true)
-    /// pin configuration.
+    /// pin configuration. Inherited from the SH1106, not described in the SH1107 datasheet.
     ComPinConfig(bool),
     /// Set up display clock.
-    /// First value is oscillator frequency, increasing with higher value
-    /// Second value is divide ratio - 1
+    /// First value is oscillator frequency from 0-15, increasing with higher value
+    /// Second value is divide ratio - 1, from 0-15
     DisplayClockDiv(u8, u8),
-    /// Set up phase 1 and 2 of precharge period. each value is from 0-63
+    /// Set up precharge (first value) and discharge (second value) periods in DCLKs, each from
+    /// 1-15
     PreChargePeriod(u8, u8),
     /// Set Vcomh Deselect level
     VcomhDeselect(VcomhLevel),
+    /// Enable or disable the DC-DC converter. Second value is the switching frequency from 0-7.
+    /// Display must be off when performing this command.
+    DcDc(bool, u8),
+    /// Enter read-modify-write mode. Reads no longer advance the column address, writes do.
+    ReadModifyWrite,
+    /// Leave read-modify-write mode, restoring the column address from before
+    /// [`ReadModifyWrite`](#variant.ReadModifyWrite)
+    End,
     /// NOOP
     Noop,
-    /// Enable charge pump
-    ChargePump(bool),
 }
 
 impl Command {
-    /// Send command to sh1107
+    /// Send command to sh1107. Fails with `InvalidArgument` without sending anything if an
+    /// argument is outside of the range given in the datasheet.
     pub fn send<DI>(self, iface: &mut DI) -> Result<(), DI::Error>
     where
         DI: DisplayInterface,
     {
-        // Transform command into a fixed size array of 7 u8 and the real length for sending
-        let (data, len) = match self {
+        self.check_range()?;
+        let (data, len) = self.encode();
+
+        // Send command over the interface
+        iface.send_commands(&data[0..len])
+    }
+
+    /// Send command to sh1107 over an asynchronous interface
+    #[cfg(feature = "async")]
+    pub async fn send_async<DI>(self, iface: &mut DI) -> Result<(), DI::Error>
+    where
+        DI: AsyncDisplayInterface,
+    {
+        self.check_range()?;
+        let (data, len) = self.encode();
+
+        iface.send_commands(&data[0..len]).await
+    }
+
+    /// Transform command into a fixed size array of 7 u8 and the real length for sending
+    fn encode(self) -> ([u8; 7], usize) {
+        match self {
             Command::Contrast(val) => ([0x81, val, 0, 0, 0, 0, 0], 2),
             Command::AllOn(on) => ([0xA4 | (on as u8), 0, 0, 0, 0, 0, 0], 1),
             Command::Invert(inv) => ([0xA6 | (inv as u8), 0, 0, 0, 0, 0, 0], 1),
             Command::DisplayOn(on) => ([0xAE | (on as u8), 0, 0, 0, 0, 0, 0], 1),
             Command::ColumnAddressLow(addr) => ([0xF & addr, 0, 0, 0, 0, 0, 0], 1),
-            Command::ColumnAddressHigh(addr) => ([0x10 | (0xF & addr), 0, 0, 0, 0, 0, 0], 1),
+            Command::ColumnAddressHigh(addr) => ([0x10 | (0x7 & addr), 0, 0, 0, 0, 0, 0], 1),
             Command::PageAddress(page) => ([0xB0 | (page as u8), 0, 0, 0, 0, 0, 0], 1),
-            Command::StartLine(line) => ([0x40 | (0x3F & line), 0, 0, 0, 0, 0, 0], 1),
+            Command::AddressMode(mode) => ([0x20 | (mode as u8), 0, 0, 0, 0, 0, 0], 1),
+            Command::StartLine(line) => ([0xDC, 0x7F & line, 0, 0, 0, 0, 0], 2),
             Command::SegmentRemap(remap) => ([0xA0 | (remap as u8), 0, 0, 0, 0, 0, 0], 1),
-            Command::Multiplex(ratio) => ([0xA8, ratio, 0, 0, 0, 0, 0], 2),
+            Command::Multiplex(ratio) => ([0xA8, 0x7F & ratio, 0, 0, 0, 0, 0], 2),
             Command::ReverseComDir(rev) => ([0xC0 | ((rev as u8) << 3), 0, 0, 0, 0, 0, 0], 1),
-            Command::DisplayOffset(offset) => ([0xD3, offset, 0, 0, 0, 0, 0], 2),
+            Command::DisplayOffset(offset) => ([0xD3, 0x7F & offset, 0, 0, 0, 0, 0], 2),
             Command::ComPinConfig(alt) => ([0xDA, 0x02 | ((alt as u8) << 4), 0, 0, 0, 0, 0], 2),
             Command::DisplayClockDiv(fosc, div) => {
                 ([0xD5, ((0xF & fosc) << 4) | (0xF & div), 0, 0, 0, 0, 0], 2)
@@ -77,16 +113,50 @@ impl Command {
                 [0xD9, ((0xF & phase2) << 4) | (0xF & phase1), 0, 0, 0, 0, 0],
                 2,
             ),
-            Command::VcomhDeselect(level) => ([0xDB, (level as u8) << 4, 0, 0, 0, 0, 0], 2),
+            Command::VcomhDeselect(level) => ([0xDB, level.value(), 0, 0, 0, 0, 0], 2),
+            Command::DcDc(en, freq) => (
+                [0xAD, 0x80 | ((0x7 & freq) << 1) | (en as u8), 0, 0, 0, 0, 0],
+                2,
+            ),
+            Command::ReadModifyWrite => ([0xE0, 0, 0, 0, 0, 0, 0], 1),
+            Command::End => ([0xEE, 0, 0, 0, 0, 0, 0], 1),
             Command::Noop => ([0xE3, 0, 0, 0, 0, 0, 0], 1),
-            Command::ChargePump(en) => ([0xAD, 0x8A | (en as u8), 0, 0, 0, 0, 0], 2),
+        }
+    }
+
+    /// Reject arguments outside of the range given in the datasheet
+    fn check_range(&self) -> Result<(), DriverError> {
+        let valid = match *self {
+            Command::ColumnAddressLow(addr) => addr <= 0xF,
+            Command::ColumnAddressHigh(addr) => addr <= 0x7,
+            Command::StartLine(line) => line <= 0x7F,
+            Command::Multiplex(ratio) => ratio <= 0x7F,
+            Command::DisplayOffset(offset) => offset <= 0x7F,
+            Command::DisplayClockDiv(fosc, div) => fosc <= 0xF && div <= 0xF,
+            Command::PreChargePeriod(phase1, phase2) => {
+                (1..=0xF).contains(&phase1) && (1..=0xF).contains(&phase2)
+            }
+            Command::DcDc(_, freq) => freq <= 0x7,
+            _ => true,
         };
 
-        // Send command over the interface
-        iface.send_commands(&data[0..len])
+        if valid {
+            Ok(())
+        } else {
+            Err(DriverError::InvalidArgument)
+        }
     }
 }
 
+/// Memory addressing mode
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AddressMode {
+    /// Column address advances after each byte, page address stays the same
+    Page = 0,
+    /// Page address advances after each byte, column address advances after the last page
+    Vertical = 1,
+}
+
 /// Display page
 #[derive(Debug, Clone, Copy)]
 pub enum Page {
@@ -124,9 +194,15 @@ pub enum Page {
     Page15 = 15,
 }
 
-impl From<u8> for Page {
-    fn from(val: u8) -> Page {
-        match val / 8 {
+/// Error returned when converting a row outside of display RAM into a [`Page`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct InvalidPage;
+
+impl Page {
+    /// Page containing the given row of display RAM. Rows past the end of display RAM wrap around.
+    pub(crate) fn from_row(row: u8) -> Page {
+        match (row / 8) & 0xF {
             0 => Page::Page0,
             1 => Page::Page1,
             2 => Page::Page2,
@@ -142,44 +218,129 @@ impl From<u8> for Page {
             12 => Page::Page12,
             13 => Page::Page13,
             14 => Page::Page14,
-            15 => Page::Page15,
-            _ => panic!("Page too high"),
+            _ => Page::Page15,
         }
     }
 }
 
-/// Frame interval
-#[derive(Debug, Clone, Copy)]
-#[allow(dead_code)]
-pub enum NFrames {
-    /// 2 Frames
-    F2 = 0b111,
-    /// 3 Frames
-    F3 = 0b100,
-    /// 4 Frames
-    F4 = 0b101,
-    /// 5 Frames
-    F5 = 0b000,
-    /// 25 Frames
-    F25 = 0b110,
-    /// 64 Frames
-    F64 = 0b001,
-    /// 128 Frames
-    F128 = 0b010,
-    /// 256 Frames
-    F256 = 0b011,
+/// Page containing the given row of display RAM, from 0-127
+impl TryFrom<u8> for Page {
+    type Error = InvalidPage;
+
+    fn try_from(row: u8) -> Result<Page, InvalidPage> {
+        if row < 128 {
+            Ok(Page::from_row(row))
+        } else {
+            Err(InvalidPage)
+        }
+    }
 }
 
 /// Vcomh Deselect level
-#[derive(Debug, Clone, Copy)]
-#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum VcomhLevel {
     /// 0.65 * Vcc
-    V065 = 0b001,
+    V065,
     /// 0.77 * Vcc
-    V077 = 0b010,
+    V077,
     /// 0.83 * Vcc
-    V083 = 0b011,
+    V083,
     /// Auto
-    Auto = 0b100,
+    Auto,
+    /// Any of the 256 steps supported by the SH1107, as sent with the command
+    Raw(u8),
+}
+
+impl VcomhLevel {
+    /// Command argument selecting this level
+    fn value(self) -> u8 {
+        match self {
+            VcomhLevel::V065 => 0b001 << 4,
+            VcomhLevel::V077 => 0b010 << 4,
+            VcomhLevel::V083 => 0b011 << 4,
+            VcomhLevel::Auto => 0b100 << 4,
+            VcomhLevel::Raw(value) => value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::convert::TryFrom;
+
+    use super::{AddressMode, Command, InvalidPage, Page, VcomhLevel};
+    use crate::{interface::mock::MockInterface, Error};
+
+    fn assert_bytes(cmd: Command, expected: &[u8]) {
+        let mut iface = MockInterface::default();
+        cmd.send(&mut iface).unwrap();
+        assert_eq!(iface.commands(), [expected]);
+    }
+
+    #[test]
+    fn single_byte_commands() {
+        assert_bytes(Command::AllOn(false), &[0xA4]);
+        assert_bytes(Command::AllOn(true), &[0xA5]);
+        assert_bytes(Command::Invert(false), &[0xA6]);
+        assert_bytes(Command::Invert(true), &[0xA7]);
+        assert_bytes(Command::DisplayOn(false), &[0xAE]);
+        assert_bytes(Command::DisplayOn(true), &[0xAF]);
+        assert_bytes(Command::SegmentRemap(false), &[0xA0]);
+        assert_bytes(Command::SegmentRemap(true), &[0xA1]);
+        assert_bytes(Command::ReverseComDir(false), &[0xC0]);
+        assert_bytes(Command::ReverseComDir(true), &[0xC8]);
+        assert_bytes(Command::ReadModifyWrite, &[0xE0]);
+        assert_bytes(Command::End, &[0xEE]);
+        assert_bytes(Command::Noop, &[0xE3]);
+    }
+
+    #[test]
+    fn addressing_commands() {
+        assert_bytes(Command::ColumnAddressLow(0x0A), &[0x0A]);
+        assert_bytes(Command::ColumnAddressHigh(0x07), &[0x17]);
+        assert_bytes(Command::PageAddress(Page::Page0), &[0xB0]);
+        assert_bytes(Command::PageAddress(Page::Page15), &[0xBF]);
+        assert_bytes(Command::AddressMode(AddressMode::Page), &[0x20]);
+        assert_bytes(Command::AddressMode(AddressMode::Vertical), &[0x21]);
+    }
+
+    #[test]
+    fn double_byte_commands() {
+        assert_bytes(Command::Contrast(0x80), &[0x81, 0x80]);
+        assert_bytes(Command::StartLine(0), &[0xDC, 0x00]);
+        assert_bytes(Command::StartLine(127), &[0xDC, 0x7F]);
+        assert_bytes(Command::Multiplex(127), &[0xA8, 0x7F]);
+        assert_bytes(Command::DisplayOffset(0x60), &[0xD3, 0x60]);
+        assert_bytes(Command::DisplayOffset(127), &[0xD3, 0x7F]);
+        assert_bytes(Command::ComPinConfig(true), &[0xDA, 0x12]);
+        assert_bytes(Command::DisplayClockDiv(0x5, 0x1), &[0xD5, 0x51]);
+        assert_bytes(Command::PreChargePeriod(0x2, 0x2), &[0xD9, 0x22]);
+        assert_bytes(Command::VcomhDeselect(VcomhLevel::Auto), &[0xDB, 0x40]);
+        assert_bytes(Command::VcomhDeselect(VcomhLevel::Raw(0x35)), &[0xDB, 0x35]);
+        assert_bytes(Command::DcDc(false, 0x0), &[0xAD, 0x80]);
+        assert_bytes(Command::DcDc(true, 0x5), &[0xAD, 0x8B]);
+        assert_bytes(Command::DcDc(true, 0x7), &[0xAD, 0x8F]);
+    }
+
+    #[test]
+    fn page_from_row() {
+        assert!(matches!(Page::try_from(0), Ok(Page::Page0)));
+        assert!(matches!(Page::try_from(9), Ok(Page::Page1)));
+        assert!(matches!(Page::try_from(127), Ok(Page::Page15)));
+        assert!(matches!(Page::try_from(128), Err(InvalidPage)));
+    }
+
+    #[test]
+    fn out_of_range_arguments_are_rejected() {
+        for cmd in [
+            Command::StartLine(128),
+            Command::PreChargePeriod(0, 0xF),
+            Command::DisplayClockDiv(0x10, 0),
+            Command::DcDc(true, 0x8),
+        ] {
+            let mut iface = MockInterface::default();
+            assert_eq!(cmd.send(&mut iface), Err(Error::InvalidArgument));
+            assert!(iface.transfers.is_empty());
+        }
+    }
 }
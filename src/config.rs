@@ -0,0 +1,186 @@
+//! Panel configuration
+//!
+//! The SH1107 needs a few analog and timing settings which depend on the OLED glass it drives
+//! rather than on the controller. Panels from different vendors flicker or look washed out when
+//! these are off. [`PanelConfig`] holds every tunable of the init sequence and is passed to
+//! [`Builder::with_panel_config`](../builder/struct.Builder.html#method.with_panel_config):
+//!
+//! ```rust,ignore
+//! let config = PanelConfig {
+//!     contrast: 0x2F,
+//!     precharge_period: 2,
+//!     discharge_period: 2,
+//!     ..PanelConfig::for_size(DisplaySize::Display128x128)
+//! };
+//!
+//! let display: GraphicsMode<_, DisplaySize128x128> = Builder::new()
+//!     .with_size(DisplaySize128x128)
+//!     .with_panel_config(config)
+//!     .connect_i2c(i2c)?
+//!     .into();
+//! ```
+
+pub use crate::command::VcomhLevel;
+use crate::displaysize::DisplaySize;
+
+/// Invalid driver configuration, returned by the `connect` methods of
+/// [`Builder`](../builder/struct.Builder.html)
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ConfigError {
+    /// Oscillator frequency outside of 0-15
+    OscillatorFrequency,
+    /// Clock divide ratio outside of 1-16
+    ClockDivide,
+    /// Precharge period outside of 1-15
+    PrechargePeriod,
+    /// Discharge period outside of 1-15
+    DischargePeriod,
+    /// DC-DC switching frequency outside of 0-7
+    DcDcFrequency,
+    /// Display offset outside of 0-127
+    DisplayOffset,
+    /// I2C address other than 0x3C or 0x3D
+    I2cAddress,
+    /// I2C chunk length outside of 1-128
+    I2cChunkLen,
+    /// Display size which doesn't fit into display RAM
+    DisplaySize,
+}
+
+/// Settings sent to the display by `init`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PanelConfig {
+    /// Oscillator frequency from 0-15, increasing with higher value
+    pub oscillator_frequency: u8,
+    /// Display clock divide ratio from 1-16
+    pub clock_divide: u8,
+    /// Contrast, higher number is higher contrast
+    pub contrast: u8,
+    /// Precharge period in display clocks from 1-15
+    pub precharge_period: u8,
+    /// Discharge period in display clocks from 1-15
+    pub discharge_period: u8,
+    /// VCOMH deselect level
+    pub vcomh_level: VcomhLevel,
+    /// Use the internal DC-DC converter. Disable it when the panel is driven by an external VPP
+    /// supply.
+    pub dc_dc: bool,
+    /// DC-DC switching frequency from 0-7
+    pub dc_dc_frequency: u8,
+    /// Vertical shift of the COM lines from 0-127
+    pub display_offset: u8,
+    /// Send the SH1106 COM pin configuration command with the alternative (`Some(true)`) or
+    /// sequential (`Some(false)`) configuration. The command isn't part of the SH1107 command set,
+    /// so it is only sent for panels which need it.
+    pub alternative_com_pins: Option<bool>,
+}
+
+impl PanelConfig {
+    /// Settings which work for most panels of the given size
+    pub fn for_size(size: DisplaySize) -> Self {
+        PanelConfig {
+            oscillator_frequency: 0x8,
+            clock_divide: 1,
+            contrast: 0x80,
+            precharge_period: 0x1,
+            discharge_period: 0xF,
+            vcomh_level: VcomhLevel::Auto,
+            dc_dc: true,
+            dc_dc_frequency: 0x5,
+            display_offset: size.row_offset(),
+            alternative_com_pins: None,
+        }
+    }
+
+    /// Check that all settings are within the ranges given in the datasheet
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.oscillator_frequency > 0xF {
+            return Err(ConfigError::OscillatorFrequency);
+        }
+        if !(1..=16).contains(&self.clock_divide) {
+            return Err(ConfigError::ClockDivide);
+        }
+        if !(1..=0xF).contains(&self.precharge_period) {
+            return Err(ConfigError::PrechargePeriod);
+        }
+        if !(1..=0xF).contains(&self.discharge_period) {
+            return Err(ConfigError::DischargePeriod);
+        }
+        if self.dc_dc_frequency > 0x7 {
+            return Err(ConfigError::DcDcFrequency);
+        }
+        if self.display_offset > 0x7F {
+            return Err(ConfigError::DisplayOffset);
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for PanelConfig {
+    fn default() -> Self {
+        Self::for_size(DisplaySize::Display128x64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConfigError, PanelConfig};
+    use crate::displaysize::DisplaySize;
+
+    #[test]
+    fn validate_rejects_values_outside_datasheet_ranges() {
+        let config = PanelConfig::for_size(DisplaySize::Display64x128);
+        assert_eq!(config.validate(), Ok(()));
+
+        let cases = [
+            (
+                PanelConfig {
+                    oscillator_frequency: 16,
+                    ..config
+                },
+                ConfigError::OscillatorFrequency,
+            ),
+            (
+                PanelConfig {
+                    clock_divide: 0,
+                    ..config
+                },
+                ConfigError::ClockDivide,
+            ),
+            (
+                PanelConfig {
+                    precharge_period: 0,
+                    ..config
+                },
+                ConfigError::PrechargePeriod,
+            ),
+            (
+                PanelConfig {
+                    discharge_period: 16,
+                    ..config
+                },
+                ConfigError::DischargePeriod,
+            ),
+            (
+                PanelConfig {
+                    dc_dc_frequency: 8,
+                    ..config
+                },
+                ConfigError::DcDcFrequency,
+            ),
+            (
+                PanelConfig {
+                    display_offset: 128,
+                    ..config
+                },
+                ConfigError::DisplayOffset,
+            ),
+        ];
+
+        for (config, error) in cases {
+            assert_eq!(config.validate(), Err(error));
+        }
+    }
+}
@@ -7,23 +7,38 @@ pub enum DisplaySize {
     Display64x128,
     /// 128 by 64 pixels
     Display128x64,
-    /// 128 by 64 pixels without 2px X offset
+    /// 128 by 64 pixels, the same as `Display128x64`. The SH1106 modules this driver started out
+    /// with needed a 2px X offset, which doesn't fit into the 128 columns of the SH1107.
     Display128x64NoOffset,
     /// 128 by 32 pixels
     Display128x32,
-    /// 132 by 64 pixels
-    Display132x64,
+    /// 128 by 128 pixels
+    Display128x128,
+    /// Any window of the 128 by 128 pixel display RAM. Use
+    /// [`custom_display_size!`](../macro.custom_display_size.html) to define a matching
+    /// [`DisplaySizeType`].
+    Custom {
+        /// Width in pixels
+        width: u8,
+        /// Height in pixels, a multiple of 8
+        height: u8,
+        /// First column of display RAM connected to the panel
+        column_offset: u8,
+        /// First row of display RAM connected to the panel
+        row_offset: u8,
+    },
 }
 
 impl DisplaySize {
     /// Get integral dimensions from DisplaySize
-    pub fn dimensions(self) -> (u8, u8) {
+    pub const fn dimensions(self) -> (u8, u8) {
         match self {
             DisplaySize::Display64x128 => (64, 128),
             DisplaySize::Display128x64 => (128, 64),
             DisplaySize::Display128x64NoOffset => (128, 64),
             DisplaySize::Display128x32 => (128, 32),
-            DisplaySize::Display132x64 => (132, 64),
+            DisplaySize::Display128x128 => (128, 128),
+            DisplaySize::Custom { width, height, .. } => (width, height),
         }
     }
 
@@ -31,10 +46,130 @@ impl DisplaySize {
     pub fn column_offset(self) -> u8 {
         match self {
             DisplaySize::Display64x128 => 2,
-            DisplaySize::Display128x64 => 2,
+            DisplaySize::Display128x64 => 0,
             DisplaySize::Display128x64NoOffset => 0,
-            DisplaySize::Display128x32 => 2,
-            DisplaySize::Display132x64 => 0,
+            DisplaySize::Display128x32 => 0,
+            DisplaySize::Display128x128 => 0,
+            DisplaySize::Custom { column_offset, .. } => column_offset,
+        }
+    }
+
+    /// Get the panel row offset from DisplaySize. This is the default display offset sent by
+    /// `init`.
+    pub fn row_offset(self) -> u8 {
+        match self {
+            DisplaySize::Display64x128 => 0x60,
+            DisplaySize::Display128x64
+            | DisplaySize::Display128x64NoOffset
+            | DisplaySize::Display128x32
+            | DisplaySize::Display128x128 => 0,
+            DisplaySize::Custom { row_offset, .. } => row_offset,
         }
     }
 }
+
+/// Type level display size
+///
+/// Implemented by the zero sized `DisplaySize*` marker types which are passed to
+/// [`Builder::with_size`](../builder/struct.Builder.html#method.with_size). Carrying the size in
+/// the type lets buffered modes allocate exactly as much RAM as the panel needs. Marker types for
+/// other panels can be defined with [`custom_display_size!`](../macro.custom_display_size.html).
+pub trait DisplaySizeType: Copy {
+    /// Runtime description of this display size
+    const SIZE: DisplaySize;
+
+    /// Framebuffer storage holding exactly one bit per pixel
+    type Buffer: AsRef<[u8]> + AsMut<[u8]>;
+
+    /// Create a new, cleared framebuffer
+    fn new_buffer() -> Self::Buffer;
+}
+
+macro_rules! display_size {
+    ($(#[$doc:meta])* $name:ident => $variant:ident, $width:expr, $height:expr) => {
+        $(#[$doc])*
+        #[derive(Clone, Copy)]
+        pub struct $name;
+
+        impl DisplaySizeType for $name {
+            const SIZE: DisplaySize = DisplaySize::$variant;
+
+            type Buffer = [u8; $width * $height / 8];
+
+            fn new_buffer() -> Self::Buffer {
+                [0; $width * $height / 8]
+            }
+        }
+    };
+}
+
+/// Define a display size type for a panel showing a window of the 128 by 128 pixel display RAM
+///
+/// Takes the visibility and name of the new type, followed by the width, height, column offset
+/// and row offset of the window. The height must be a multiple of 8 and the window must fit into
+/// display RAM, which is checked at compile time.
+///
+/// ```rust,ignore
+/// sh1107::custom_display_size!(pub DisplaySize96x96, 96, 96, 16, 16);
+///
+/// let display: GraphicsMode<_, DisplaySize96x96> = Builder::new()
+///     .with_size(DisplaySize96x96)
+///     .connect_i2c(i2c)
+///     .unwrap()
+///     .into();
+/// ```
+#[macro_export]
+macro_rules! custom_display_size {
+    (
+        $(#[$doc:meta])* $vis:vis $name:ident,
+        $width:expr, $height:expr, $column_offset:expr, $row_offset:expr
+    ) => {
+        $(#[$doc])*
+        #[derive(Clone, Copy)]
+        $vis struct $name;
+
+        const _: () = assert!(
+            $height % 8 == 0
+                && $width + $column_offset <= 128
+                && $height <= 128
+                && $row_offset < 128,
+            "custom display size doesn't fit into display RAM"
+        );
+
+        impl $crate::prelude::DisplaySizeType for $name {
+            const SIZE: $crate::prelude::DisplaySize = $crate::prelude::DisplaySize::Custom {
+                width: $width,
+                height: $height,
+                column_offset: $column_offset,
+                row_offset: $row_offset,
+            };
+
+            type Buffer = [u8; $width * $height / 8];
+
+            fn new_buffer() -> Self::Buffer {
+                [0; $width * $height / 8]
+            }
+        }
+    };
+}
+
+display_size!(
+    /// 64 by 128 pixels
+    DisplaySize64x128 => Display64x128, 64, 128
+);
+display_size!(
+    /// 128 by 64 pixels
+    DisplaySize128x64 => Display128x64, 128, 64
+);
+display_size!(
+    /// 128 by 64 pixels, the same as `DisplaySize128x64`
+    DisplaySize128x64NoOffset => Display128x64NoOffset, 128, 64
+);
+display_size!(
+    /// 128 by 32 pixels
+    DisplaySize128x32 => Display128x32, 128, 32
+);
+display_size!(
+    /// 128 by 128 pixels
+    DisplaySize128x128 => Display128x128, 128, 128
+);
@@ -0,0 +1,76 @@
+//! embedded-hal 1.0 support
+//!
+//! The driver takes embedded-hal 0.2 peripherals by default. Wrap embedded-hal 1.0 peripherals in
+//! [`Eh1`] to use them instead, which requires the `eh1` feature. Both can be used in the same
+//! program, e.g. a 1.0 I2C bus together with 0.2 reset pins.
+//!
+//! Wrapped buses are driven through their embedded-hal 1.0 traits:
+//!
+//! - An `I2c` bus for [`I2cInterface`](../interface/i2c/struct.I2cInterface.html), which reports
+//!   a missing acknowledge as [`Error::DeviceNotResponding`](../enum.Error.html)
+//! - An `SpiDevice` for [`SpiInterface`](../interface/spi/struct.SpiInterface.html) and
+//!   [`Spi3WireInterface`](../interface/spi3wire/struct.Spi3WireInterface.html). The device
+//!   asserts its own chip select around every write, so pass
+//!   [`NoOutputPin`](../builder/struct.NoOutputPin.html) as the interface's CS pin unless the
+//!   device has none.
+//!
+//! Wrapped `OutputPin`s and `DelayNs` delays implement the embedded-hal 0.2 traits, so they can
+//! be used as D/C pins, passed to the power sequences or used with the parallel interface:
+//!
+//! ```rust,ignore
+//! let mut display: GraphicsMode<_> = Builder::new()
+//!     .connect_i2c(Eh1::new(i2c))
+//!     .unwrap()
+//!     .into();
+//!
+//! display
+//!     .power_on(&mut Eh1::new(rst), &mut NoOutputPin::new(), &mut Eh1::new(delay))
+//!     .unwrap();
+//! display.init().unwrap();
+//! ```
+
+use crate::hal::{DelayMs, DelayNs, OutputPin, OutputPin1};
+
+/// An embedded-hal 1.0 peripheral
+#[derive(Debug, Clone, Copy)]
+pub struct Eh1<T>(T);
+
+impl<T> Eh1<T> {
+    /// Wrap an embedded-hal 1.0 bus, pin or delay
+    pub fn new(inner: T) -> Self {
+        Eh1(inner)
+    }
+
+    /// Return the wrapped peripheral
+    pub fn release(self) -> T {
+        self.0
+    }
+
+    pub(crate) fn inner(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<PIN> OutputPin for Eh1<PIN>
+where
+    PIN: OutputPin1,
+{
+    type Error = PIN::Error;
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.0.set_low()
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.0.set_high()
+    }
+}
+
+impl<DELAY> DelayMs<u8> for Eh1<DELAY>
+where
+    DELAY: DelayNs,
+{
+    fn delay_ms(&mut self, ms: u8) {
+        self.0.delay_ms(ms.into())
+    }
+}
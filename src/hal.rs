@@ -0,0 +1,25 @@
+//! embedded-hal traits used by the driver
+//!
+//! The driver is written against the embedded-hal 0.2 traits. With the `eh1` feature, the bus
+//! interfaces additionally support embedded-hal 1.0 buses wrapped in
+//! [`Eh1`](../eh1/struct.Eh1.html), see the [`eh1`](../eh1/index.html) module.
+
+pub use embedded_hal::{
+    blocking::{
+        delay::DelayMs,
+        i2c::{Write as I2c, WriteRead as I2cWriteRead},
+        spi::Write as SpiWrite,
+    },
+    digital::v2::{InputPin, OutputPin},
+};
+
+#[cfg(feature = "eh1")]
+pub use embedded_hal_1::{
+    delay::DelayNs,
+    digital::OutputPin as OutputPin1,
+    i2c::{Error as I2cError, ErrorKind as I2cErrorKind, I2c as I2c1},
+    spi::SpiDevice,
+};
+
+#[cfg(feature = "async")]
+pub use embedded_hal_async::{i2c::I2c as AsyncI2c, spi::SpiDevice as AsyncSpiDevice};
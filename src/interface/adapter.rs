@@ -0,0 +1,132 @@
+//! Adapter for interfaces from the `display-interface` crate
+//!
+//! Wraps any [`WriteOnlyDataCommand`] transport, e.g. from `display-interface-i2c`,
+//! `display-interface-spi` or `display-interface-parallel-gpio`, so it can drive the display. Use
+//! [`Builder::connect`](../../builder/struct.Builder.html#method.connect) to create one.
+
+use display_interface::{DataFormat, DisplayError, WriteOnlyDataCommand};
+
+#[cfg(feature = "async")]
+use super::AsyncDisplayInterface;
+use super::DisplayInterface;
+use crate::Error;
+#[cfg(feature = "async")]
+use display_interface::AsyncWriteOnlyDataCommand;
+
+/// Display interface wrapping a `display-interface` transport
+pub struct WriteOnlyInterface<DI> {
+    iface: DI,
+}
+
+impl<DI> WriteOnlyInterface<DI> {
+    /// Wrap a `display-interface` transport
+    pub fn new(iface: DI) -> Self {
+        Self { iface }
+    }
+
+    /// Release the wrapped transport
+    pub fn release(self) -> DI {
+        self.iface
+    }
+}
+
+impl<DI> DisplayInterface for WriteOnlyInterface<DI>
+where
+    DI: WriteOnlyDataCommand,
+{
+    type Error = Error<DisplayError>;
+
+    fn init(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn send_commands(&mut self, cmds: &[u8]) -> Result<(), Self::Error> {
+        self.iface
+            .send_commands(DataFormat::U8(cmds))
+            .map_err(Error::Comm)
+    }
+
+    fn send_data(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        self.iface
+            .send_data(DataFormat::U8(buf))
+            .map_err(Error::Comm)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<DI> AsyncDisplayInterface for WriteOnlyInterface<DI>
+where
+    DI: AsyncWriteOnlyDataCommand,
+{
+    type Error = Error<DisplayError>;
+
+    async fn init(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    async fn send_commands(&mut self, cmds: &[u8]) -> Result<(), Self::Error> {
+        self.iface
+            .send_commands(DataFormat::U8(cmds))
+            .await
+            .map_err(Error::Comm)
+    }
+
+    async fn send_data(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        self.iface
+            .send_data(DataFormat::U8(buf))
+            .await
+            .map_err(Error::Comm)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use display_interface::{DataFormat, DisplayError, WriteOnlyDataCommand};
+
+    use crate::{
+        mode::{displaymode::DisplayModeTrait, RawMode},
+        properties::Page,
+        Builder,
+    };
+
+    #[derive(Default)]
+    struct Recorder {
+        commands: Vec<u8>,
+        data: Vec<u8>,
+    }
+
+    impl WriteOnlyDataCommand for Recorder {
+        fn send_commands(&mut self, cmd: DataFormat<'_>) -> Result<(), DisplayError> {
+            match cmd {
+                DataFormat::U8(bytes) => self.commands.extend_from_slice(bytes),
+                _ => return Err(DisplayError::DataFormatNotImplemented),
+            }
+            Ok(())
+        }
+
+        fn send_data(&mut self, buf: DataFormat<'_>) -> Result<(), DisplayError> {
+            match buf {
+                DataFormat::U8(bytes) => self.data.extend_from_slice(bytes),
+                _ => return Err(DisplayError::DataFormatNotImplemented),
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn bytes_are_passed_through() {
+        let disp: RawMode<_> = Builder::new().connect(Recorder::default()).unwrap().into();
+        let mut disp = RawMode::new(disp.release().initialised());
+
+        disp.write_page(Page::Page1, 0, &[1, 2, 3]).unwrap();
+
+        let props = disp.release();
+        let recorder = &props.iface().iface;
+        assert_eq!(recorder.commands, [0xB1, 0x00, 0x10]);
+        assert_eq!(recorder.data, [1, 2, 3]);
+    }
+}
@@ -1,85 +1,387 @@
 //! SH1107 I2C Interface
 
-use hal;
+use super::{DisplayInterface, ReadableDisplayInterface};
+use crate::{
+    config::ConfigError,
+    hal::{I2c, I2cWriteRead},
+    Error,
+};
 
-use super::DisplayInterface;
-use crate::{command::Page, Error};
+#[cfg(feature = "eh1")]
+use crate::{
+    eh1::Eh1,
+    hal::{I2c1, I2cError, I2cErrorKind},
+};
+
+#[cfg(feature = "async")]
+use super::AsyncDisplayInterface;
+#[cfg(feature = "async")]
+use crate::hal::AsyncI2c;
+
+/// Largest number of data bytes sent in a single I2C transaction
+pub const MAX_CHUNK_LEN: usize = 128;
+
+/// Number of data bytes sent in a single I2C transaction unless configured otherwise
+pub const DEFAULT_CHUNK_LEN: usize = 64;
 
 /// SH1107 I2C communication interface
 pub struct I2cInterface<I2C> {
     i2c: I2C,
     addr: u8,
+    chunk_len: usize,
 }
 
-impl<I2C> I2cInterface<I2C>
-where
-    I2C: hal::blocking::i2c::Write,
-{
+impl<I2C> I2cInterface<I2C> {
     /// Create new sh1107 I2C interface
     pub fn new(i2c: I2C, addr: u8) -> Self {
-        Self { i2c, addr }
+        Self {
+            i2c,
+            addr,
+            chunk_len: DEFAULT_CHUNK_LEN,
+        }
+    }
+
+    /// Set the number of data bytes sent per I2C transaction. Longer chunks need fewer
+    /// transactions, shorter ones keep the bus free for other devices more often. Lengths outside
+    /// of `1..=MAX_CHUNK_LEN` are rejected with `ConfigError::I2cChunkLen`.
+    pub fn with_chunk_len(self, chunk_len: usize) -> Result<Self, ConfigError> {
+        if !(1..=MAX_CHUNK_LEN).contains(&chunk_len) {
+            return Err(ConfigError::I2cChunkLen);
+        }
+
+        Ok(Self { chunk_len, ..self })
+    }
+
+    /// Consume the interface and return the I2C bus
+    pub fn release(self) -> I2C {
+        self.i2c
     }
 }
 
 impl<I2C, CommE> DisplayInterface for I2cInterface<I2C>
 where
-    I2C: hal::blocking::i2c::Write<Error = CommE>,
+    I2C: I2c<Error = CommE>,
 {
-    type Error = Error<CommE, ()>;
+    type Error = Error<CommE>;
 
     fn init(&mut self) -> Result<(), Self::Error> {
         Ok(())
     }
 
     fn send_commands(&mut self, cmds: &[u8]) -> Result<(), Self::Error> {
-        // Copy over given commands to new aray to prefix with command identifier
-        let mut writebuf: [u8; 8] = [0; 8];
-        writebuf[1..=cmds.len()].copy_from_slice(&cmds);
+        let addr = self.addr;
+
+        write_commands(cmds, |bytes| self.i2c.write(addr, bytes)).map_err(Error::Comm)
+    }
+
+    fn send_data(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        let addr = self.addr;
+
+        write_data(buf, self.chunk_len, |bytes| self.i2c.write(addr, bytes)).map_err(Error::Comm)
+    }
+}
+
+impl<I2C, CommE> ReadableDisplayInterface for I2cInterface<I2C>
+where
+    I2C: I2c<Error = CommE> + I2cWriteRead<Error = CommE>,
+{
+    fn read_status(&mut self) -> Result<u8, Self::Error> {
+        // The D/C bit of the control byte selects between the status byte and display RAM
+        let mut status = [0];
+
+        self.i2c
+            .write_read(self.addr, &[0x00], &mut status)
+            .map_err(Error::Comm)?;
+
+        Ok(status[0])
+    }
+
+    fn read_data(&mut self, buf: &mut [u8]) -> Result<(), Self::Error> {
+        if buf.is_empty() {
+            return Ok(());
+        }
 
         self.i2c
-            .write(self.addr, &writebuf[..=cmds.len()])
+            .write_read(self.addr, &[0x40], buf)
             .map_err(Error::Comm)
     }
+}
+
+#[cfg(feature = "eh1")]
+impl<I2C> DisplayInterface for I2cInterface<Eh1<I2C>>
+where
+    I2C: I2c1,
+{
+    type Error = Error<I2C::Error>;
+
+    fn init(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn send_commands(&mut self, cmds: &[u8]) -> Result<(), Self::Error> {
+        let addr = self.addr;
+
+        write_commands(cmds, |bytes| self.i2c.inner().write(addr, bytes)).map_err(bus_error)
+    }
 
     fn send_data(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
-        // TODO: figure out a way to pass chunklen in, should likely always be 64 for sh1107, but the sh1106 was doing 128
-        const CHUNKLEN: usize = 64;
+        let addr = self.addr;
 
-        const BUFLEN: usize = CHUNKLEN + 1;
+        write_data(buf, self.chunk_len, |bytes| {
+            self.i2c.inner().write(addr, bytes)
+        })
+        .map_err(bus_error)
+    }
+}
 
-        // Noop if the data buffer is empty
+#[cfg(feature = "eh1")]
+impl<I2C> ReadableDisplayInterface for I2cInterface<Eh1<I2C>>
+where
+    I2C: I2c1,
+{
+    fn read_status(&mut self) -> Result<u8, Self::Error> {
+        let mut status = [0];
+
+        self.i2c
+            .inner()
+            .write_read(self.addr, &[0x00], &mut status)
+            .map_err(bus_error)?;
+
+        Ok(status[0])
+    }
+
+    fn read_data(&mut self, buf: &mut [u8]) -> Result<(), Self::Error> {
         if buf.is_empty() {
             return Ok(());
         }
 
-        let mut page = Page::Page0 as u8;
+        self.i2c
+            .inner()
+            .write_read(self.addr, &[0x40], buf)
+            .map_err(bus_error)
+    }
+}
 
-        // Display width plus 4 start bytes
-        let mut writebuf: [u8; BUFLEN] = [0; BUFLEN];
+#[cfg(feature = "async")]
+impl<I2C> AsyncDisplayInterface for I2cInterface<I2C>
+where
+    I2C: AsyncI2c,
+{
+    type Error = Error<I2C::Error>;
 
-        writebuf[0] = 0x40; // Following bytes are data bytes
+    async fn init(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
 
-        for chunk in buf.chunks(CHUNKLEN) {
-            // Copy over all data from buffer, leaving the data command byte intact
-            writebuf[1..BUFLEN].copy_from_slice(&chunk);
+    async fn send_commands(&mut self, cmds: &[u8]) -> Result<(), Self::Error> {
+        let (writebuf, len) = command_frame(cmds);
 
+        self.i2c
+            .write(self.addr, &writebuf[..len])
+            .await
+            .map_err(bus_error)
+    }
+
+    async fn send_data(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        let mut frame = DataFrame::new();
+
+        for chunk in buf.chunks(self.chunk_len) {
             self.i2c
-                .write(
-                    self.addr,
-                    &[
-                        0x00, // Command
-                        page, // Page address
-                        0x02, // Lower column address
-                        0x10, // Upper column address (always zero, base is 10h)
-                    ],
-                )
-                .map_err(Error::Comm)?;
-
-            self.i2c.write(self.addr, &writebuf).map_err(Error::Comm)?;
-
-            page += 1;
+                .write(self.addr, frame.fill(chunk))
+                .await
+                .map_err(bus_error)?;
         }
 
         Ok(())
     }
 }
+
+/// Wrap an embedded-hal 1.0 bus error, reporting a missing acknowledge as `DeviceNotResponding`
+#[cfg(feature = "eh1")]
+fn bus_error<CommE: I2cError>(error: CommE) -> Error<CommE> {
+    match error.kind() {
+        I2cErrorKind::NoAcknowledge(_) => Error::DeviceNotResponding,
+        _ => Error::Comm(error),
+    }
+}
+
+/// Send a batch of commands in a single transaction
+fn write_commands<E>(cmds: &[u8], write: impl FnOnce(&[u8]) -> Result<(), E>) -> Result<(), E> {
+    let (writebuf, len) = command_frame(cmds);
+
+    write(&writebuf[..len])
+}
+
+/// Send data bytes in transactions of up to `chunk_len` bytes
+fn write_data<E>(
+    buf: &[u8],
+    chunk_len: usize,
+    mut write: impl FnMut(&[u8]) -> Result<(), E>,
+) -> Result<(), E> {
+    // Data bytes are written wherever the display's address pointer currently is, which is owned
+    // by `DisplayProperties`
+    let mut frame = DataFrame::new();
+
+    for chunk in buf.chunks(chunk_len) {
+        write(frame.fill(chunk))?;
+    }
+
+    Ok(())
+}
+
+/// Transaction buffer for a chunk of data bytes, prefixed with the data control byte
+struct DataFrame([u8; MAX_CHUNK_LEN + 1]);
+
+impl DataFrame {
+    fn new() -> Self {
+        let mut writebuf = [0; MAX_CHUNK_LEN + 1];
+        writebuf[0] = 0x40; // Following bytes are data bytes
+
+        DataFrame(writebuf)
+    }
+
+    /// Copy over a chunk of at most `MAX_CHUNK_LEN` bytes, leaving the control byte intact, and
+    /// return the bytes to write
+    fn fill(&mut self, chunk: &[u8]) -> &[u8] {
+        self.0[1..=chunk.len()].copy_from_slice(chunk);
+
+        &self.0[..=chunk.len()]
+    }
+}
+
+/// Copy over given commands to a new array to prefix them with the command identifier, returning
+/// the array and the number of bytes to write
+fn command_frame(cmds: &[u8]) -> ([u8; 8], usize) {
+    let mut writebuf: [u8; 8] = [0; 8];
+    writebuf[1..=cmds.len()].copy_from_slice(cmds);
+
+    (writebuf, cmds.len() + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::{I2cInterface, MAX_CHUNK_LEN};
+    use crate::{
+        config::ConfigError,
+        interface::{DisplayInterface, ReadableDisplayInterface},
+    };
+    #[cfg(feature = "eh1")]
+    use crate::{Eh1, Error};
+
+    /// Value returned for every byte read
+    const READ_VALUE: u8 = 0xA5;
+
+    /// Address which isn't acknowledged
+    #[cfg(feature = "eh1")]
+    const NACK_ADDR: u8 = 0x3E;
+
+    #[derive(Default)]
+    struct I2cSpy {
+        writes: Vec<(u8, Vec<u8>)>,
+    }
+
+    impl crate::hal::I2c for I2cSpy {
+        type Error = ();
+
+        fn write(&mut self, addr: u8, bytes: &[u8]) -> Result<(), ()> {
+            self.writes.push((addr, bytes.to_vec()));
+            Ok(())
+        }
+    }
+
+    impl crate::hal::I2cWriteRead for I2cSpy {
+        type Error = ();
+
+        fn write_read(&mut self, addr: u8, bytes: &[u8], buffer: &mut [u8]) -> Result<(), ()> {
+            self.writes.push((addr, bytes.to_vec()));
+            buffer.fill(READ_VALUE);
+            Ok(())
+        }
+    }
+
+    #[cfg(feature = "eh1")]
+    impl embedded_hal_1::i2c::ErrorType for I2cSpy {
+        type Error = embedded_hal_1::i2c::ErrorKind;
+    }
+
+    #[cfg(feature = "eh1")]
+    impl crate::hal::I2c1 for I2cSpy {
+        fn transaction(
+            &mut self,
+            addr: u8,
+            operations: &mut [embedded_hal_1::i2c::Operation<'_>],
+        ) -> Result<(), Self::Error> {
+            if addr == NACK_ADDR {
+                return Err(embedded_hal_1::i2c::ErrorKind::NoAcknowledge(
+                    embedded_hal_1::i2c::NoAcknowledgeSource::Address,
+                ));
+            }
+            for operation in operations {
+                match operation {
+                    embedded_hal_1::i2c::Operation::Write(bytes) => {
+                        self.writes.push((addr, bytes.to_vec()))
+                    }
+                    embedded_hal_1::i2c::Operation::Read(buffer) => buffer.fill(READ_VALUE),
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn data_is_chunked_without_addressing() {
+        let mut iface = I2cInterface::new(I2cSpy::default(), 0x3D)
+            .with_chunk_len(16)
+            .unwrap();
+
+        iface.send_data(&[0xAB; 40]).unwrap();
+
+        let writes = &iface.i2c.writes;
+        assert_eq!(writes.len(), 3);
+        for (write, len) in writes.iter().zip([16, 16, 8]) {
+            assert_eq!(write.0, 0x3D);
+            assert_eq!(write.1[0], 0x40);
+            assert_eq!(write.1[1..], [0xAB; 40][..len]);
+        }
+    }
+
+    #[test]
+    fn reads_select_status_or_data_with_control_byte() {
+        let mut iface = I2cInterface::new(I2cSpy::default(), 0x3C);
+        let mut buf = [0; 4];
+
+        assert_eq!(iface.read_status().unwrap(), READ_VALUE);
+        iface.read_data(&mut buf).unwrap();
+
+        assert_eq!(buf, [READ_VALUE; 4]);
+        assert_eq!(
+            iface.i2c.writes,
+            [(0x3C, [0x00].to_vec()), (0x3C, [0x40].to_vec())]
+        );
+    }
+
+    #[test]
+    fn chunk_len_outside_of_range_is_rejected() {
+        for chunk_len in [0, MAX_CHUNK_LEN + 1] {
+            assert!(matches!(
+                I2cInterface::new(I2cSpy::default(), 0x3C).with_chunk_len(chunk_len),
+                Err(ConfigError::I2cChunkLen)
+            ));
+        }
+    }
+
+    #[cfg(feature = "eh1")]
+    #[test]
+    fn missing_acknowledge_means_device_not_responding() {
+        let mut iface = I2cInterface::new(Eh1::new(I2cSpy::default()), NACK_ADDR);
+
+        assert_eq!(
+            iface.send_commands(&[0xAF]),
+            Err(Error::DeviceNotResponding)
+        );
+    }
+}
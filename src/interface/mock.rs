@@ -0,0 +1,146 @@
+//! Recording interface for unit tests
+
+extern crate std;
+
+use std::vec::Vec;
+
+#[cfg(feature = "async")]
+use super::AsyncDisplayInterface;
+use super::{DisplayInterface, DmaDisplayInterface, ReadableDisplayInterface};
+use crate::Error;
+
+/// A single transfer seen by [`MockInterface`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Transfer {
+    /// A batch of command bytes
+    Commands(Vec<u8>),
+    /// A batch of data bytes
+    Data(Vec<u8>),
+    /// A read of the given number of bytes
+    Read(usize),
+}
+
+/// Display interface which records everything sent to it
+#[derive(Default)]
+pub struct MockInterface {
+    pub transfers: Vec<Transfer>,
+    /// Number of polls left before the background transfer finishes
+    pub pending_polls: usize,
+}
+
+impl MockInterface {
+    /// All command batches, in order
+    pub fn commands(&self) -> Vec<Vec<u8>> {
+        self.transfers
+            .iter()
+            .filter_map(|t| match t {
+                Transfer::Commands(c) => Some(c.clone()),
+                Transfer::Data(_) | Transfer::Read(_) => None,
+            })
+            .collect()
+    }
+
+    /// All data bytes, concatenated
+    pub fn data(&self) -> Vec<u8> {
+        self.transfers
+            .iter()
+            .filter_map(|t| match t {
+                Transfer::Data(d) => Some(d.clone()),
+                Transfer::Commands(_) | Transfer::Read(_) => None,
+            })
+            .flatten()
+            .collect()
+    }
+
+    /// Forget everything recorded so far
+    pub fn clear(&mut self) {
+        self.transfers.clear();
+    }
+}
+
+impl DisplayInterface for MockInterface {
+    type Error = Error<()>;
+
+    fn init(&mut self) -> Result<(), Error<()>> {
+        Ok(())
+    }
+
+    fn send_commands(&mut self, cmds: &[u8]) -> Result<(), Error<()>> {
+        self.transfers.push(Transfer::Commands(cmds.to_vec()));
+        Ok(())
+    }
+
+    fn send_data(&mut self, buf: &[u8]) -> Result<(), Error<()>> {
+        self.transfers.push(Transfer::Data(buf.to_vec()));
+        Ok(())
+    }
+}
+
+/// Background transfers are recorded like regular data and finish on the second poll
+// Safety: the buffer is copied before `start_data` returns
+#[allow(unsafe_code)]
+unsafe impl DmaDisplayInterface for MockInterface {
+    fn start_data(&mut self, buf: &[u8]) -> Result<(), Error<()>> {
+        assert_eq!(
+            self.pending_polls, 0,
+            "transfer started while another one is running"
+        );
+        self.pending_polls = 1;
+        DisplayInterface::send_data(self, buf)
+    }
+
+    fn poll_data(&mut self) -> nb::Result<(), Error<()>> {
+        if self.pending_polls > 0 {
+            self.pending_polls -= 1;
+            return Err(nb::Error::WouldBlock);
+        }
+        Ok(())
+    }
+}
+
+/// Reads return the status `0x40` (display off) and data bytes counting up from 0
+impl ReadableDisplayInterface for MockInterface {
+    fn read_status(&mut self) -> Result<u8, Error<()>> {
+        self.transfers.push(Transfer::Read(1));
+        Ok(0x40)
+    }
+
+    fn read_data(&mut self, buf: &mut [u8]) -> Result<(), Error<()>> {
+        self.transfers.push(Transfer::Read(buf.len()));
+        for (byte, value) in buf.iter_mut().zip(0..) {
+            *byte = value;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncDisplayInterface for MockInterface {
+    type Error = Error<()>;
+
+    async fn init(&mut self) -> Result<(), Error<()>> {
+        DisplayInterface::init(self)
+    }
+
+    async fn send_commands(&mut self, cmds: &[u8]) -> Result<(), Error<()>> {
+        DisplayInterface::send_commands(self, cmds)
+    }
+
+    async fn send_data(&mut self, buf: &[u8]) -> Result<(), Error<()>> {
+        DisplayInterface::send_data(self, buf)
+    }
+}
+
+/// Run a future which never waits on anything to completion
+#[cfg(feature = "async")]
+pub fn block_on<F: core::future::Future>(future: F) -> F::Output {
+    use core::task::{Context, Poll, Waker};
+
+    let mut future = core::pin::pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+
+    match future.as_mut().poll(&mut cx) {
+        Poll::Ready(output) => output,
+        Poll::Pending => panic!("mock futures complete immediately"),
+    }
+}
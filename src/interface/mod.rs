@@ -1,9 +1,24 @@
-//! sh1107 Communication Interface (I2C/SPI)
+//! sh1107 Communication Interface (I2C/SPI/parallel)
 //!
-//! These are the two supported interfaces for communicating with the display. They're used by the
+//! These are the supported interfaces for communicating with the display. They're used by the
 //! [builder](../builder/index.html) methods
-//! [connect_i2c](../builder/struct.Builder.html#method.connect_i2c) and
-//! [connect_spi](../builder/struct.Builder.html#method.connect_spi).
+//! [connect_i2c](../builder/struct.Builder.html#method.connect_i2c),
+//! [connect_spi](../builder/struct.Builder.html#method.connect_spi),
+//! [connect_spi_3wire](../builder/struct.Builder.html#method.connect_spi_3wire) and
+//! [connect_parallel](../builder/struct.Builder.html#method.connect_parallel).
+//!
+//! With the `display-interface` feature, any transport implementing
+//! `display_interface::WriteOnlyDataCommand` can be used as well, wrapped in a
+//! [`WriteOnlyInterface`](adapter/struct.WriteOnlyInterface.html) by
+//! [connect](../builder/struct.Builder.html#method.connect).
+//!
+//! Every interface can be consumed with `release` to get the bus back, e.g.
+//! `display.release().release().release()` turns a mode into its properties, the properties into
+//! the interface and the interface into the bus. To share one bus between several displays or
+//! other drivers, pass a bus proxy instead of the peripheral itself. The [shared](shared/index.html)
+//! module provides proxies for embedded-hal 0.2. With the `eh1` feature, `&mut I2C` and the
+//! devices from the `embedded-hal-bus` crate can be used by wrapping them in
+//! [`Eh1`](../eh1/struct.Eh1.html).
 //!
 //! The types that these interfaces define are quite lengthy, so it is recommended that you create
 //! a type alias. Here's an example for the I2C1 on an STM32F103xx:
@@ -48,13 +63,28 @@
 //! >;
 //! ```
 
+#[cfg(feature = "display-interface")]
+pub mod adapter;
 pub mod i2c;
+#[cfg(test)]
+pub(crate) mod mock;
+pub mod parallel;
+pub mod shared;
 pub mod spi;
+pub mod spi3wire;
+
+use crate::DriverError;
 
 /// A method of communicating with sh1107
 pub trait DisplayInterface {
-    /// Interface error type
-    type Error;
+    /// Interface error type. Errors detected by the driver, e.g. coordinates outside of the
+    /// display, are converted into it from [`DriverError`].
+    ///
+    /// Interfaces implemented outside of this crate need an error type implementing
+    /// `From<DriverError>`. The simplest way is to report [`Error`](../enum.Error.html), wrapping
+    /// bus errors in `Error::Comm` and pin errors in `Error::Pin`. Interfaces with an error type
+    /// of their own have to add a variant for driver errors.
+    type Error: From<DriverError>;
 
     /// Initialize device.
     fn init(&mut self) -> Result<(), Self::Error>;
@@ -64,4 +94,56 @@ pub trait DisplayInterface {
     fn send_data(&mut self, buf: &[u8]) -> Result<(), Self::Error>;
 }
 
-pub use self::{i2c::I2cInterface, spi::SpiInterface};
+/// A method of communicating with sh1107 which can send display data in the background, e.g.
+/// using DMA
+///
+/// # Safety
+///
+/// Implementations may keep reading the buffer passed to `start_data` after it returned, under the
+/// same contract as [`DmaWrite`](spi/trait.DmaWrite.html): only read it, stop once `poll_data`
+/// returned `Ok` or an error, and never let a transfer outlive the interface. The buffer is kept
+/// unchanged in turn, see the safety section of
+/// [`GraphicsMode::flush_dma`](../mode/graphics/struct.GraphicsMode.html#method.flush_dma).
+#[allow(unsafe_code)]
+pub unsafe trait DmaDisplayInterface: DisplayInterface {
+    /// Start sending data to display. The buffer must not be modified until
+    /// [`poll_data`](#tymethod.poll_data) reports that the transfer finished.
+    fn start_data(&mut self, buf: &[u8]) -> Result<(), Self::Error>;
+    /// Check whether the transfer started by [`start_data`](#tymethod.start_data) finished.
+    fn poll_data(&mut self) -> nb::Result<(), Self::Error>;
+}
+
+/// A method of communicating with sh1107 which can also read from it
+///
+/// The SH1107 can only be read over I2C and the parallel interfaces. SPI is write-only.
+pub trait ReadableDisplayInterface: DisplayInterface {
+    /// Read the status byte.
+    fn read_status(&mut self) -> Result<u8, Self::Error>;
+    /// Read data from display RAM at the current address. Note that the display returns a dummy
+    /// byte on the first read after the address was set.
+    fn read_data(&mut self, buf: &mut [u8]) -> Result<(), Self::Error>;
+}
+
+/// An asynchronous method of communicating with sh1107
+///
+/// The counterpart of [`DisplayInterface`] for `async` executors, implemented by the I2C and SPI
+/// interfaces when they wrap `embedded-hal-async` buses.
+#[cfg(feature = "async")]
+#[allow(async_fn_in_trait)]
+pub trait AsyncDisplayInterface {
+    /// Interface error type, see [`DisplayInterface::Error`]
+    type Error: From<DriverError>;
+
+    /// Initialize device.
+    async fn init(&mut self) -> Result<(), Self::Error>;
+    /// Send a batch of up to 8 commands to display.
+    async fn send_commands(&mut self, cmd: &[u8]) -> Result<(), Self::Error>;
+    /// Send data to display.
+    async fn send_data(&mut self, buf: &[u8]) -> Result<(), Self::Error>;
+}
+
+#[cfg(feature = "display-interface")]
+pub use self::adapter::WriteOnlyInterface;
+pub use self::{
+    i2c::I2cInterface, parallel::ParallelInterface, spi::SpiInterface, spi3wire::Spi3WireInterface,
+};
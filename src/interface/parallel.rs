@@ -0,0 +1,572 @@
+//! sh1107 8-bit parallel interface
+//!
+//! The SH1107 supports both Intel 8080 and Motorola 6800 style parallel buses. Both use the same
+//! two strobe pins, named after their 8080 roles here:
+//!
+//! | Pin  | 8080                        | 6800                          |
+//! |------|-----------------------------|-------------------------------|
+//! | `WR` | Write strobe, active low    | R/W select, held low to write |
+//! | `RD` | Read strobe, held high      | E enable, active high         |
+//!
+//! The data lines D0-D7 are driven by an [`OutputBus`], e.g. a [`Generic8BitBus`] of eight
+//! `OutputPin`s. Use [`Builder::connect_parallel`](../../builder/struct.Builder.html#method.connect_parallel)
+//! to create an interface, selecting the protocol with
+//! [`Builder::with_parallel_protocol`](../../builder/struct.Builder.html#method.with_parallel_protocol).
+
+use super::{DisplayInterface, ReadableDisplayInterface};
+use crate::{
+    hal::{InputPin, OutputPin},
+    Error,
+};
+
+/// Parallel bus protocol
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParallelProtocol {
+    /// Intel 8080, latching data on the rising edge of `WR`
+    I8080,
+    /// Motorola 6800, latching data on the falling edge of `E` (the `RD` pin)
+    M6800,
+}
+
+/// Eight data lines of a parallel bus
+pub trait OutputBus {
+    /// Bus error type
+    type Error;
+
+    /// Drive the data lines to `value`, with D0 as the least significant bit
+    fn set_value(&mut self, value: u8) -> Result<(), Self::Error>;
+}
+
+/// Eight data lines of a parallel bus which can also be read
+///
+/// Before a read, the interface calls `set_input` so the data lines stop being driven, then
+/// strobes the display and samples the lines with `read_value`. Implementations are expected to
+/// switch back to outputs in the next `set_value`.
+pub trait BidirectionalBus: OutputBus {
+    /// Stop driving the data lines so the display can drive them
+    fn set_input(&mut self) -> Result<(), Self::Error>;
+
+    /// Sample the data lines, with D0 as the least significant bit
+    fn read_value(&mut self) -> Result<u8, Self::Error>;
+}
+
+/// Parallel bus made of eight individual output pins, D0 to D7
+///
+/// Only pins whose level changes from the previous value are written.
+pub struct Generic8BitBus<P0, P1, P2, P3, P4, P5, P6, P7> {
+    pins: (P0, P1, P2, P3, P4, P5, P6, P7),
+    last: Option<u8>,
+}
+
+impl<P0, P1, P2, P3, P4, P5, P6, P7> Generic8BitBus<P0, P1, P2, P3, P4, P5, P6, P7> {
+    /// Create a new bus from the pins D0 to D7
+    pub fn new(pins: (P0, P1, P2, P3, P4, P5, P6, P7)) -> Self {
+        Self { pins, last: None }
+    }
+
+    /// Release the data pins
+    pub fn release(self) -> (P0, P1, P2, P3, P4, P5, P6, P7) {
+        self.pins
+    }
+}
+
+impl<P0, P1, P2, P3, P4, P5, P6, P7, PinE> OutputBus
+    for Generic8BitBus<P0, P1, P2, P3, P4, P5, P6, P7>
+where
+    P0: OutputPin<Error = PinE>,
+    P1: OutputPin<Error = PinE>,
+    P2: OutputPin<Error = PinE>,
+    P3: OutputPin<Error = PinE>,
+    P4: OutputPin<Error = PinE>,
+    P5: OutputPin<Error = PinE>,
+    P6: OutputPin<Error = PinE>,
+    P7: OutputPin<Error = PinE>,
+{
+    type Error = PinE;
+
+    fn set_value(&mut self, value: u8) -> Result<(), PinE> {
+        let changed = match self.last {
+            Some(last) => last ^ value,
+            None => 0xFF,
+        };
+
+        macro_rules! set_pin {
+            ($pin:expr, $bit:expr) => {
+                if changed & (1 << $bit) != 0 {
+                    if value & (1 << $bit) != 0 {
+                        $pin.set_high()?;
+                    } else {
+                        $pin.set_low()?;
+                    }
+                }
+            };
+        }
+
+        // Forget the previous value until all pins are written, so a failed write is retried
+        // in full
+        self.last = None;
+
+        set_pin!(self.pins.0, 0);
+        set_pin!(self.pins.1, 1);
+        set_pin!(self.pins.2, 2);
+        set_pin!(self.pins.3, 3);
+        set_pin!(self.pins.4, 4);
+        set_pin!(self.pins.5, 5);
+        set_pin!(self.pins.6, 6);
+        set_pin!(self.pins.7, 7);
+
+        self.last = Some(value);
+
+        Ok(())
+    }
+}
+
+/// Reading needs pins which can be both written and read, like open drain outputs with pull-ups.
+/// `set_input` releases the data lines by setting every pin high, so the display can pull them
+/// low.
+impl<P0, P1, P2, P3, P4, P5, P6, P7, PinE> BidirectionalBus
+    for Generic8BitBus<P0, P1, P2, P3, P4, P5, P6, P7>
+where
+    P0: OutputPin<Error = PinE> + InputPin<Error = PinE>,
+    P1: OutputPin<Error = PinE> + InputPin<Error = PinE>,
+    P2: OutputPin<Error = PinE> + InputPin<Error = PinE>,
+    P3: OutputPin<Error = PinE> + InputPin<Error = PinE>,
+    P4: OutputPin<Error = PinE> + InputPin<Error = PinE>,
+    P5: OutputPin<Error = PinE> + InputPin<Error = PinE>,
+    P6: OutputPin<Error = PinE> + InputPin<Error = PinE>,
+    P7: OutputPin<Error = PinE> + InputPin<Error = PinE>,
+{
+    fn set_input(&mut self) -> Result<(), PinE> {
+        self.set_value(0xFF)
+    }
+
+    fn read_value(&mut self) -> Result<u8, PinE> {
+        let mut value = 0;
+
+        macro_rules! read_pin {
+            ($pin:expr, $bit:expr) => {
+                if $pin.is_high()? {
+                    value |= 1 << $bit;
+                }
+            };
+        }
+
+        read_pin!(self.pins.0, 0);
+        read_pin!(self.pins.1, 1);
+        read_pin!(self.pins.2, 2);
+        read_pin!(self.pins.3, 3);
+        read_pin!(self.pins.4, 4);
+        read_pin!(self.pins.5, 5);
+        read_pin!(self.pins.6, 6);
+        read_pin!(self.pins.7, 7);
+
+        Ok(value)
+    }
+}
+
+/// 8-bit parallel display interface
+///
+/// This combines the data bus with the data/command, write, read and chip select pins. If chip
+/// select is tied low, [`NoOutputPin`](../../builder/struct.NoOutputPin.html) can be used instead.
+pub struct ParallelInterface<BUS, DC, WR, RD, CS> {
+    bus: BUS,
+    dc: DC,
+    wr: WR,
+    rd: RD,
+    cs: CS,
+    protocol: ParallelProtocol,
+}
+
+impl<BUS, DC, WR, RD, CS> ParallelInterface<BUS, DC, WR, RD, CS> {
+    /// Create new parallel interface for communication with sh1107
+    pub fn new(bus: BUS, dc: DC, wr: WR, rd: RD, cs: CS, protocol: ParallelProtocol) -> Self {
+        Self {
+            bus,
+            dc,
+            wr,
+            rd,
+            cs,
+            protocol,
+        }
+    }
+
+    /// Consume the interface and return the data bus and pins
+    pub fn release(self) -> (BUS, DC, WR, RD, CS) {
+        (self.bus, self.dc, self.wr, self.rd, self.cs)
+    }
+}
+
+impl<BUS, DC, WR, RD, CS, CommE, PinE> ParallelInterface<BUS, DC, WR, RD, CS>
+where
+    BUS: OutputBus<Error = CommE>,
+    DC: OutputPin<Error = PinE>,
+    WR: OutputPin<Error = PinE>,
+    RD: OutputPin<Error = PinE>,
+    CS: OutputPin<Error = PinE>,
+{
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Error<CommE, PinE>> {
+        for byte in bytes {
+            self.bus.set_value(*byte).map_err(Error::Comm)?;
+
+            match self.protocol {
+                ParallelProtocol::I8080 => {
+                    self.wr.set_low().map_err(Error::Pin)?;
+                    self.wr.set_high().map_err(Error::Pin)?;
+                }
+                ParallelProtocol::M6800 => {
+                    self.rd.set_high().map_err(Error::Pin)?;
+                    self.rd.set_low().map_err(Error::Pin)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn transfer(&mut self, data: bool, bytes: &[u8]) -> Result<(), Error<CommE, PinE>> {
+        self.select(data)?;
+        self.write_bytes(bytes)?;
+        self.cs.set_high().map_err(Error::Pin)
+    }
+
+    /// Assert chip select and set the D/C pin
+    fn select(&mut self, data: bool) -> Result<(), Error<CommE, PinE>> {
+        self.cs.set_low().map_err(Error::Pin)?;
+
+        // 1 = data, 0 = command
+        if data {
+            self.dc.set_high().map_err(Error::Pin)
+        } else {
+            self.dc.set_low().map_err(Error::Pin)
+        }
+    }
+}
+
+impl<BUS, DC, WR, RD, CS, CommE, PinE> ParallelInterface<BUS, DC, WR, RD, CS>
+where
+    BUS: BidirectionalBus<Error = CommE>,
+    DC: OutputPin<Error = PinE>,
+    WR: OutputPin<Error = PinE>,
+    RD: OutputPin<Error = PinE>,
+    CS: OutputPin<Error = PinE>,
+{
+    fn read_bytes(&mut self, data: bool, buf: &mut [u8]) -> Result<(), Error<CommE, PinE>> {
+        self.select(data)?;
+
+        // Release the data lines before the display starts driving them
+        self.bus.set_input().map_err(Error::Comm)?;
+
+        if self.protocol == ParallelProtocol::M6800 {
+            self.wr.set_high().map_err(Error::Pin)?;
+        }
+
+        for byte in buf.iter_mut() {
+            match self.protocol {
+                ParallelProtocol::I8080 => {
+                    self.rd.set_low().map_err(Error::Pin)?;
+                    *byte = self.bus.read_value().map_err(Error::Comm)?;
+                    self.rd.set_high().map_err(Error::Pin)?;
+                }
+                ParallelProtocol::M6800 => {
+                    self.rd.set_high().map_err(Error::Pin)?;
+                    *byte = self.bus.read_value().map_err(Error::Comm)?;
+                    self.rd.set_low().map_err(Error::Pin)?;
+                }
+            }
+        }
+
+        if self.protocol == ParallelProtocol::M6800 {
+            self.wr.set_low().map_err(Error::Pin)?;
+        }
+
+        self.cs.set_high().map_err(Error::Pin)
+    }
+}
+
+impl<BUS, DC, WR, RD, CS, CommE, PinE> DisplayInterface for ParallelInterface<BUS, DC, WR, RD, CS>
+where
+    BUS: OutputBus<Error = CommE>,
+    DC: OutputPin<Error = PinE>,
+    WR: OutputPin<Error = PinE>,
+    RD: OutputPin<Error = PinE>,
+    CS: OutputPin<Error = PinE>,
+{
+    type Error = Error<CommE, PinE>;
+
+    fn init(&mut self) -> Result<(), Self::Error> {
+        // Put both strobes into their idle state for the selected protocol
+        match self.protocol {
+            ParallelProtocol::I8080 => {
+                self.wr.set_high().map_err(Error::Pin)?;
+                self.rd.set_high().map_err(Error::Pin)?;
+            }
+            ParallelProtocol::M6800 => {
+                self.wr.set_low().map_err(Error::Pin)?;
+                self.rd.set_low().map_err(Error::Pin)?;
+            }
+        }
+
+        self.cs.set_high().map_err(Error::Pin)
+    }
+
+    fn send_commands(&mut self, cmds: &[u8]) -> Result<(), Self::Error> {
+        self.transfer(false, cmds)
+    }
+
+    fn send_data(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        self.transfer(true, buf)
+    }
+}
+
+impl<BUS, DC, WR, RD, CS, CommE, PinE> ReadableDisplayInterface
+    for ParallelInterface<BUS, DC, WR, RD, CS>
+where
+    BUS: BidirectionalBus<Error = CommE>,
+    DC: OutputPin<Error = PinE>,
+    WR: OutputPin<Error = PinE>,
+    RD: OutputPin<Error = PinE>,
+    CS: OutputPin<Error = PinE>,
+{
+    fn read_status(&mut self) -> Result<u8, Self::Error> {
+        let mut status = [0];
+        self.read_bytes(false, &mut status)?;
+
+        Ok(status[0])
+    }
+
+    fn read_data(&mut self, buf: &mut [u8]) -> Result<(), Self::Error> {
+        self.read_bytes(true, buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use core::{cell::RefCell, convert::Infallible};
+    use std::vec::Vec;
+
+    use super::{BidirectionalBus, Generic8BitBus, OutputBus, ParallelInterface, ParallelProtocol};
+    use crate::interface::{DisplayInterface, ReadableDisplayInterface};
+
+    type Log = RefCell<Vec<(&'static str, u8)>>;
+
+    struct LogPin<'a> {
+        name: &'static str,
+        log: &'a Log,
+    }
+
+    impl LogPin<'_> {
+        fn set(&mut self, level: u8) -> Result<(), Infallible> {
+            self.log.borrow_mut().push((self.name, level));
+            Ok(())
+        }
+    }
+
+    impl crate::hal::OutputPin for LogPin<'_> {
+        type Error = Infallible;
+
+        fn set_low(&mut self) -> Result<(), Infallible> {
+            self.set(0)
+        }
+
+        fn set_high(&mut self) -> Result<(), Infallible> {
+            self.set(1)
+        }
+    }
+
+    impl crate::hal::InputPin for LogPin<'_> {
+        type Error = Infallible;
+
+        fn is_high(&self) -> Result<bool, Infallible> {
+            // Odd data lines read high
+            Ok(self.name.ends_with(['1', '3', '5', '7']))
+        }
+
+        fn is_low(&self) -> Result<bool, Infallible> {
+            self.is_high().map(|high| !high)
+        }
+    }
+
+    struct LogBus<'a> {
+        log: &'a Log,
+    }
+
+    impl OutputBus for LogBus<'_> {
+        type Error = Infallible;
+
+        fn set_value(&mut self, value: u8) -> Result<(), Infallible> {
+            self.log.borrow_mut().push(("D", value));
+            Ok(())
+        }
+    }
+
+    impl BidirectionalBus for LogBus<'_> {
+        fn set_input(&mut self) -> Result<(), Infallible> {
+            self.log.borrow_mut().push(("D", 0xFF));
+            Ok(())
+        }
+
+        fn read_value(&mut self) -> Result<u8, Infallible> {
+            self.log.borrow_mut().push(("read", 0));
+            Ok(0x5A)
+        }
+    }
+
+    type LogInterface<'a> =
+        ParallelInterface<LogBus<'a>, LogPin<'a>, LogPin<'a>, LogPin<'a>, LogPin<'a>>;
+
+    fn interface(log: &Log, protocol: ParallelProtocol) -> LogInterface<'_> {
+        let pin = |name| LogPin { name, log };
+
+        ParallelInterface::new(
+            LogBus { log },
+            pin("DC"),
+            pin("WR"),
+            pin("RD"),
+            pin("CS"),
+            protocol,
+        )
+    }
+
+    #[test]
+    fn i8080_strobes_write() {
+        let log = Log::default();
+        let mut iface = interface(&log, ParallelProtocol::I8080);
+
+        iface.send_commands(&[0xAF]).unwrap();
+        iface.send_data(&[0x12]).unwrap();
+
+        assert_eq!(
+            log.into_inner(),
+            [
+                ("CS", 0),
+                ("DC", 0),
+                ("D", 0xAF),
+                ("WR", 0),
+                ("WR", 1),
+                ("CS", 1),
+                ("CS", 0),
+                ("DC", 1),
+                ("D", 0x12),
+                ("WR", 0),
+                ("WR", 1),
+                ("CS", 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn m6800_strobes_enable() {
+        let log = Log::default();
+        let mut iface = interface(&log, ParallelProtocol::M6800);
+
+        iface.init().unwrap();
+        iface.send_data(&[0x34]).unwrap();
+
+        assert_eq!(
+            log.into_inner(),
+            [
+                ("WR", 0),
+                ("RD", 0),
+                ("CS", 1),
+                ("CS", 0),
+                ("DC", 1),
+                ("D", 0x34),
+                ("RD", 1),
+                ("RD", 0),
+                ("CS", 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_release_the_bus_before_strobing() {
+        let log = Log::default();
+        let mut iface = interface(&log, ParallelProtocol::I8080);
+
+        assert_eq!(iface.read_status().unwrap(), 0x5A);
+        assert_eq!(
+            log.into_inner(),
+            [
+                ("CS", 0),
+                ("DC", 0),
+                ("D", 0xFF),
+                ("RD", 0),
+                ("read", 0),
+                ("RD", 1),
+                ("CS", 1),
+            ]
+        );
+
+        let log = Log::default();
+        let mut iface = interface(&log, ParallelProtocol::M6800);
+        let mut buf = [0; 2];
+        iface.read_data(&mut buf).unwrap();
+
+        assert_eq!(buf, [0x5A; 2]);
+        assert_eq!(
+            log.into_inner(),
+            [
+                ("CS", 0),
+                ("DC", 1),
+                ("D", 0xFF),
+                ("WR", 1),
+                ("RD", 1),
+                ("read", 0),
+                ("RD", 0),
+                ("RD", 1),
+                ("read", 0),
+                ("RD", 0),
+                ("WR", 0),
+                ("CS", 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn generic_bus_reads_released_pins() {
+        let log = Log::default();
+        let pin = |name| LogPin { name, log: &log };
+        let mut bus = Generic8BitBus::new((
+            pin("D0"),
+            pin("D1"),
+            pin("D2"),
+            pin("D3"),
+            pin("D4"),
+            pin("D5"),
+            pin("D6"),
+            pin("D7"),
+        ));
+
+        bus.set_value(0x00).unwrap();
+        log.borrow_mut().clear();
+        bus.set_input().unwrap();
+
+        assert_eq!(log.borrow().len(), 8);
+        assert!(log.borrow().iter().all(|(_, level)| *level == 1));
+        assert_eq!(bus.read_value().unwrap(), 0xAA);
+    }
+
+    #[test]
+    fn generic_bus_only_writes_changed_pins() {
+        let log = Log::default();
+        let pin = |name| LogPin { name, log: &log };
+        let mut bus = Generic8BitBus::new((
+            pin("D0"),
+            pin("D1"),
+            pin("D2"),
+            pin("D3"),
+            pin("D4"),
+            pin("D5"),
+            pin("D6"),
+            pin("D7"),
+        ));
+
+        bus.set_value(0x0F).unwrap();
+        log.borrow_mut().clear();
+        bus.set_value(0x0D).unwrap();
+
+        assert_eq!(log.into_inner(), [("D1", 0)]);
+    }
+}
@@ -0,0 +1,181 @@
+//! Bus proxies for sharing one I2C or SPI peripheral between several drivers
+//!
+//! embedded-hal 0.2 doesn't implement its bus traits for references, so an interface normally
+//! takes ownership of the peripheral. The proxies in this module implement the blocking I2C and
+//! SPI write traits on top of a borrowed or shared bus instead, which lets two displays (e.g. at
+//! `0x3C` and `0x3D`) and other drivers use the same bus:
+//!
+//! ```rust,ignore
+//! let bus = RefCell::new(i2c);
+//!
+//! let mut left: GraphicsMode<_, _> = Builder::new()
+//!     .with_i2c_addr(0x3C)
+//!     .connect_i2c(RefCellBus::new(&bus))
+//!     .unwrap()
+//!     .into();
+//! let mut right: GraphicsMode<_, _> = Builder::new()
+//!     .with_i2c_addr(0x3D)
+//!     .connect_i2c(RefCellBus::new(&bus))
+//!     .unwrap()
+//!     .into();
+//! ```
+//!
+//! These proxies are for embedded-hal 0.2 buses. For embedded-hal 1.0 buses, use the devices from
+//! the `embedded-hal-bus` crate wrapped in [`Eh1`](../../eh1/struct.Eh1.html) instead.
+
+use core::cell::RefCell;
+
+use crate::hal::{I2c, I2cWriteRead, SpiWrite};
+
+/// Bus proxy wrapping a mutable reference to the bus
+pub struct BorrowedBus<'a, BUS> {
+    bus: &'a mut BUS,
+}
+
+impl<'a, BUS> BorrowedBus<'a, BUS> {
+    /// Create a new proxy borrowing `bus`
+    pub fn new(bus: &'a mut BUS) -> Self {
+        Self { bus }
+    }
+
+    fn with_bus<R>(&mut self, f: impl FnOnce(&mut BUS) -> R) -> R {
+        f(self.bus)
+    }
+}
+
+/// Bus proxy sharing the bus through a `RefCell`
+///
+/// Each transaction borrows the bus mutably, so this is only suitable for drivers used from the
+/// same execution context. Use `CriticalSectionBus` to share a bus with interrupt handlers.
+pub struct RefCellBus<'a, BUS> {
+    bus: &'a RefCell<BUS>,
+}
+
+impl<'a, BUS> RefCellBus<'a, BUS> {
+    /// Create a new proxy for the bus in `bus`
+    pub fn new(bus: &'a RefCell<BUS>) -> Self {
+        Self { bus }
+    }
+
+    fn with_bus<R>(&mut self, f: impl FnOnce(&mut BUS) -> R) -> R {
+        f(&mut self.bus.borrow_mut())
+    }
+}
+
+/// Bus proxy sharing the bus through a `critical_section::Mutex`
+///
+/// Every transaction runs inside a critical section. Requires the `critical-section` feature.
+#[cfg(feature = "critical-section")]
+pub struct CriticalSectionBus<'a, BUS> {
+    bus: &'a critical_section::Mutex<RefCell<BUS>>,
+}
+
+#[cfg(feature = "critical-section")]
+impl<'a, BUS> CriticalSectionBus<'a, BUS> {
+    /// Create a new proxy for the bus in `bus`
+    pub fn new(bus: &'a critical_section::Mutex<RefCell<BUS>>) -> Self {
+        Self { bus }
+    }
+
+    fn with_bus<R>(&mut self, f: impl FnOnce(&mut BUS) -> R) -> R {
+        critical_section::with(|cs| f(&mut self.bus.borrow_ref_mut(cs)))
+    }
+}
+
+macro_rules! bus_proxy {
+    ($name:ident) => {
+        impl<BUS> I2c for $name<'_, BUS>
+        where
+            BUS: I2c,
+        {
+            type Error = BUS::Error;
+
+            fn write(&mut self, addr: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+                self.with_bus(|bus| bus.write(addr, bytes))
+            }
+        }
+
+        impl<BUS> I2cWriteRead for $name<'_, BUS>
+        where
+            BUS: I2cWriteRead,
+        {
+            type Error = BUS::Error;
+
+            fn write_read(
+                &mut self,
+                addr: u8,
+                bytes: &[u8],
+                buffer: &mut [u8],
+            ) -> Result<(), Self::Error> {
+                self.with_bus(|bus| bus.write_read(addr, bytes, buffer))
+            }
+        }
+
+        impl<BUS> SpiWrite<u8> for $name<'_, BUS>
+        where
+            BUS: SpiWrite<u8>,
+        {
+            type Error = BUS::Error;
+
+            fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+                self.with_bus(|bus| bus.write(words))
+            }
+        }
+    };
+}
+
+bus_proxy!(BorrowedBus);
+bus_proxy!(RefCellBus);
+#[cfg(feature = "critical-section")]
+bus_proxy!(CriticalSectionBus);
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use core::cell::RefCell;
+    use std::vec::Vec;
+
+    use super::{BorrowedBus, RefCellBus};
+    use crate::interface::{DisplayInterface, I2cInterface};
+
+    #[derive(Default)]
+    struct I2cLog {
+        writes: Vec<(u8, Vec<u8>)>,
+    }
+
+    impl crate::hal::I2c for I2cLog {
+        type Error = ();
+
+        fn write(&mut self, addr: u8, bytes: &[u8]) -> Result<(), ()> {
+            self.writes.push((addr, bytes.to_vec()));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn interfaces_share_one_bus() {
+        let bus = RefCell::new(I2cLog::default());
+        let mut left = I2cInterface::new(RefCellBus::new(&bus), 0x3C);
+        let mut right = I2cInterface::new(RefCellBus::new(&bus), 0x3D);
+
+        left.send_commands(&[0xAF]).unwrap();
+        right.send_commands(&[0xAE]).unwrap();
+        left.release();
+        right.release();
+
+        let mut log = bus.into_inner();
+        let mut borrowed = I2cInterface::new(BorrowedBus::new(&mut log), 0x3C);
+        borrowed.send_data(&[0x01]).unwrap();
+        borrowed.release();
+
+        assert_eq!(
+            log.writes,
+            [
+                (0x3C, [0x00, 0xAF].to_vec()),
+                (0x3D, [0x00, 0xAE].to_vec()),
+                (0x3C, [0x40, 0x01].to_vec()),
+            ]
+        );
+    }
+}
@@ -1,34 +1,73 @@
 //! sh1107 SPI interface
 
-use hal::{self, digital::v2::OutputPin};
+use super::{DisplayInterface, DmaDisplayInterface};
+use crate::{hal::OutputPin, Error};
 
-use super::DisplayInterface;
-use crate::Error;
+use crate::hal::SpiWrite;
+
+#[cfg(feature = "eh1")]
+use crate::{eh1::Eh1, hal::SpiDevice};
+
+#[cfg(feature = "async")]
+use super::AsyncDisplayInterface;
+#[cfg(feature = "async")]
+use crate::hal::AsyncSpiDevice;
+
+/// SPI peripheral which can write a buffer in the background, e.g. using DMA
+///
+/// Implement this for your HAL's DMA capable SPI to use
+/// [`GraphicsMode::flush_dma`](../../mode/graphics/struct.GraphicsMode.html#method.flush_dma).
+///
+/// # Safety
+///
+/// The transfer started by `start_write` may keep reading `buf` after the call returned, which the
+/// borrow checker can't follow. The driver leaves `buf` unchanged until `poll_write` returns `Ok`
+/// or an error, relying on the caller of the unsafe
+/// [`GraphicsMode::flush_dma`](../../mode/graphics/struct.GraphicsMode.html#method.flush_dma) not
+/// to leak the handle driving the transfer. Implementations must:
+///
+/// - Only read from `buf`, never write to it
+/// - Stop reading `buf` once `poll_write` returned `Ok` or an error
+/// - Finish or abort a running transfer before the peripheral is used for anything else or
+///   dropped, so no transfer outlives the peripheral
+#[allow(unsafe_code)]
+pub unsafe trait DmaWrite {
+    /// Transfer error type
+    type Error;
+
+    /// Start writing `buf`
+    fn start_write(&mut self, buf: &[u8]) -> Result<(), Self::Error>;
+
+    /// Check whether the write started by `start_write` finished
+    fn poll_write(&mut self) -> nb::Result<(), Self::Error>;
+}
 
 /// SPI display interface.
 ///
-/// This combines the SPI peripheral and a data/command pin
+/// This combines the SPI peripheral, a data/command pin and a chip select pin. If chip select is
+/// tied low or driven by an embedded-hal 1.0 `SpiDevice`,
+/// [`NoOutputPin`](../../builder/struct.NoOutputPin.html) can be used instead.
 pub struct SpiInterface<SPI, DC, CS> {
     spi: SPI,
     dc: DC,
     cs: CS,
 }
 
-impl<SPI, DC, CS, CommE, PinE> SpiInterface<SPI, DC, CS>
-where
-    SPI: hal::blocking::spi::Write<u8, Error = CommE>,
-    DC: OutputPin<Error = PinE>,
-    CS: OutputPin<Error = PinE>,
-{
+impl<SPI, DC, CS> SpiInterface<SPI, DC, CS> {
     /// Create new SPI interface for communciation with sh1107
     pub fn new(spi: SPI, dc: DC, cs: CS) -> Self {
         Self { spi, dc, cs }
     }
+
+    /// Consume the interface and return the SPI bus and pins
+    pub fn release(self) -> (SPI, DC, CS) {
+        (self.spi, self.dc, self.cs)
+    }
 }
 
 impl<SPI, DC, CS, CommE, PinE> DisplayInterface for SpiInterface<SPI, DC, CS>
 where
-    SPI: hal::blocking::spi::Write<u8, Error = CommE>,
+    SPI: SpiWrite<u8, Error = CommE>,
     DC: OutputPin<Error = PinE>,
     CS: OutputPin<Error = PinE>,
 {
@@ -42,7 +81,7 @@ where
         self.cs.set_low().map_err(Error::Pin)?;
         self.dc.set_low().map_err(Error::Pin)?;
 
-        self.spi.write(&cmds).map_err(Error::Comm)?;
+        self.spi.write(cmds).map_err(Error::Comm)?;
 
         self.dc.set_high().map_err(Error::Pin)?;
         self.cs.set_high().map_err(Error::Pin)
@@ -54,7 +93,136 @@ where
         // 1 = data, 0 = command
         self.dc.set_high().map_err(Error::Pin)?;
 
-        self.spi.write(&buf).map_err(Error::Comm)?;
+        self.spi.write(buf).map_err(Error::Comm)?;
+
+        self.cs.set_high().map_err(Error::Pin)
+    }
+}
+
+// Safety: the buffer is handed to `DmaWrite`, which upholds the same contract
+#[allow(unsafe_code)]
+unsafe impl<SPI, DC, CS, CommE, PinE> DmaDisplayInterface for SpiInterface<SPI, DC, CS>
+where
+    SPI: SpiWrite<u8, Error = CommE> + DmaWrite<Error = CommE>,
+    DC: OutputPin<Error = PinE>,
+    CS: OutputPin<Error = PinE>,
+{
+    fn start_data(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        self.cs.set_low().map_err(Error::Pin)?;
+
+        // 1 = data, 0 = command
+        self.dc.set_high().map_err(Error::Pin)?;
+
+        self.spi.start_write(buf).map_err(Error::Comm)
+    }
+
+    fn poll_data(&mut self) -> nb::Result<(), Self::Error> {
+        self.spi.poll_write().map_err(|e| e.map(Error::Comm))?;
+
+        self.cs
+            .set_high()
+            .map_err(|e| nb::Error::Other(Error::Pin(e)))
+    }
+}
+
+#[cfg(feature = "eh1")]
+impl<SPI, DC, CS, PinE> DisplayInterface for SpiInterface<Eh1<SPI>, DC, CS>
+where
+    SPI: SpiDevice,
+    DC: OutputPin<Error = PinE>,
+    CS: OutputPin<Error = PinE>,
+{
+    type Error = Error<SPI::Error, PinE>;
+
+    fn init(&mut self) -> Result<(), Self::Error> {
+        self.cs.set_high().map_err(Error::Pin)
+    }
+
+    fn send_commands(&mut self, cmds: &[u8]) -> Result<(), Self::Error> {
+        self.cs.set_low().map_err(Error::Pin)?;
+        self.dc.set_low().map_err(Error::Pin)?;
+
+        self.spi.inner().write(cmds).map_err(Error::Comm)?;
+
+        self.dc.set_high().map_err(Error::Pin)?;
+        self.cs.set_high().map_err(Error::Pin)
+    }
+
+    fn send_data(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        self.cs.set_low().map_err(Error::Pin)?;
+
+        // 1 = data, 0 = command
+        self.dc.set_high().map_err(Error::Pin)?;
+
+        self.spi.inner().write(buf).map_err(Error::Comm)?;
+
+        self.cs.set_high().map_err(Error::Pin)
+    }
+}
+
+/// DMA transfers don't go through the `SpiDevice`, so it can't assert chip select for them. Use
+/// a device without chip select and pass the CS pin to the interface instead, which then drives
+/// it for every transfer.
+// Safety: the buffer is handed to `DmaWrite`, which upholds the same contract
+#[cfg(feature = "eh1")]
+#[allow(unsafe_code)]
+unsafe impl<SPI, DC, CS, CommE, PinE> DmaDisplayInterface for SpiInterface<Eh1<SPI>, DC, CS>
+where
+    SPI: SpiDevice<Error = CommE> + DmaWrite<Error = CommE>,
+    DC: OutputPin<Error = PinE>,
+    CS: OutputPin<Error = PinE>,
+{
+    fn start_data(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        self.cs.set_low().map_err(Error::Pin)?;
+
+        // 1 = data, 0 = command
+        self.dc.set_high().map_err(Error::Pin)?;
+
+        self.spi.inner().start_write(buf).map_err(Error::Comm)
+    }
+
+    fn poll_data(&mut self) -> nb::Result<(), Self::Error> {
+        self.spi
+            .inner()
+            .poll_write()
+            .map_err(|e| e.map(Error::Comm))?;
+
+        self.cs
+            .set_high()
+            .map_err(|e| nb::Error::Other(Error::Pin(e)))
+    }
+}
+
+#[cfg(feature = "async")]
+impl<SPI, DC, CS, PinE> AsyncDisplayInterface for SpiInterface<SPI, DC, CS>
+where
+    SPI: AsyncSpiDevice,
+    DC: OutputPin<Error = PinE>,
+    CS: OutputPin<Error = PinE>,
+{
+    type Error = Error<SPI::Error, PinE>;
+
+    async fn init(&mut self) -> Result<(), Self::Error> {
+        self.cs.set_high().map_err(Error::Pin)
+    }
+
+    async fn send_commands(&mut self, cmds: &[u8]) -> Result<(), Self::Error> {
+        self.cs.set_low().map_err(Error::Pin)?;
+        self.dc.set_low().map_err(Error::Pin)?;
+
+        self.spi.write(cmds).await.map_err(Error::Comm)?;
+
+        self.dc.set_high().map_err(Error::Pin)?;
+        self.cs.set_high().map_err(Error::Pin)
+    }
+
+    async fn send_data(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        self.cs.set_low().map_err(Error::Pin)?;
+
+        // 1 = data, 0 = command
+        self.dc.set_high().map_err(Error::Pin)?;
+
+        self.spi.write(buf).await.map_err(Error::Comm)?;
 
         self.cs.set_high().map_err(Error::Pin)
     }
@@ -0,0 +1,191 @@
+//! sh1107 3-wire SPI interface
+//!
+//! In 3-wire mode the SH1107 has no D/C pin. Instead every byte is sent as a 9-bit word, its first
+//! bit selecting between command (0) and data (1). This interface packs eight such words into nine
+//! bytes so it can be used with a regular 8-bit SPI peripheral in mode 0 or 3, MSB first.
+//!
+//! Transfers which don't fill a complete group of eight words are padded with NOP commands, so the
+//! display never sees a partial word.
+
+use super::DisplayInterface;
+use crate::Error;
+
+use crate::hal::{OutputPin, SpiWrite};
+
+#[cfg(feature = "eh1")]
+use crate::{eh1::Eh1, hal::SpiDevice};
+
+/// NOP command, used to fill up incomplete groups of words
+const NOP: u16 = 0xE3;
+
+/// Number of groups of eight words packed before each SPI write
+const GROUPS_PER_WRITE: usize = 8;
+
+/// 3-wire SPI display interface.
+///
+/// This combines the SPI peripheral and a chip select pin. If chip select is tied low,
+/// [`NoOutputPin`](../../builder/struct.NoOutputPin.html) can be used instead.
+pub struct Spi3WireInterface<SPI, CS> {
+    spi: SPI,
+    cs: CS,
+}
+
+impl<SPI, CS> Spi3WireInterface<SPI, CS> {
+    /// Create new 3-wire SPI interface for communication with sh1107
+    pub fn new(spi: SPI, cs: CS) -> Self {
+        Self { spi, cs }
+    }
+
+    /// Consume the interface and return the SPI bus and chip select pin
+    pub fn release(self) -> (SPI, CS) {
+        (self.spi, self.cs)
+    }
+}
+
+impl<SPI, CS, CommE, PinE> DisplayInterface for Spi3WireInterface<SPI, CS>
+where
+    SPI: SpiWrite<u8, Error = CommE>,
+    CS: OutputPin<Error = PinE>,
+{
+    type Error = Error<CommE, PinE>;
+
+    fn init(&mut self) -> Result<(), Self::Error> {
+        self.cs.set_high().map_err(Error::Pin)
+    }
+
+    fn send_commands(&mut self, cmds: &[u8]) -> Result<(), Self::Error> {
+        self.cs.set_low().map_err(Error::Pin)?;
+        write_packed(false, cmds, |bytes| self.spi.write(bytes)).map_err(Error::Comm)?;
+        self.cs.set_high().map_err(Error::Pin)
+    }
+
+    fn send_data(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        self.cs.set_low().map_err(Error::Pin)?;
+        write_packed(true, buf, |bytes| self.spi.write(bytes)).map_err(Error::Comm)?;
+        self.cs.set_high().map_err(Error::Pin)
+    }
+}
+
+#[cfg(feature = "eh1")]
+impl<SPI, CS, PinE> DisplayInterface for Spi3WireInterface<Eh1<SPI>, CS>
+where
+    SPI: SpiDevice,
+    CS: OutputPin<Error = PinE>,
+{
+    type Error = Error<SPI::Error, PinE>;
+
+    fn init(&mut self) -> Result<(), Self::Error> {
+        self.cs.set_high().map_err(Error::Pin)
+    }
+
+    fn send_commands(&mut self, cmds: &[u8]) -> Result<(), Self::Error> {
+        self.cs.set_low().map_err(Error::Pin)?;
+        write_packed(false, cmds, |bytes| self.spi.inner().write(bytes)).map_err(Error::Comm)?;
+        self.cs.set_high().map_err(Error::Pin)
+    }
+
+    fn send_data(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        self.cs.set_low().map_err(Error::Pin)?;
+        write_packed(true, buf, |bytes| self.spi.inner().write(bytes)).map_err(Error::Comm)?;
+        self.cs.set_high().map_err(Error::Pin)
+    }
+}
+
+/// Pack `bytes` into 9-bit words with the given D/C bit and hand them to `write` in chunks
+fn write_packed<E>(
+    data: bool,
+    bytes: &[u8],
+    mut write: impl FnMut(&[u8]) -> Result<(), E>,
+) -> Result<(), E> {
+    let mut writebuf = [0; 9 * GROUPS_PER_WRITE];
+
+    for chunk in bytes.chunks(8 * GROUPS_PER_WRITE) {
+        let mut len = 0;
+
+        for group in chunk.chunks(8) {
+            writebuf[len..len + 9].copy_from_slice(&pack_group(data, group));
+            len += 9;
+        }
+
+        write(&writebuf[..len])?;
+    }
+
+    Ok(())
+}
+
+/// Pack up to eight bytes into nine bytes of 9-bit words, filling up the group with NOPs
+fn pack_group(data: bool, bytes: &[u8]) -> [u8; 9] {
+    let mut packed = [0; 9];
+    let mut packed_len = 0;
+    let mut acc: u32 = 0;
+    let mut acc_bits = 0;
+
+    for word in 0..8 {
+        let word = match bytes.get(word) {
+            Some(byte) => ((data as u16) << 8) | *byte as u16,
+            None => NOP,
+        };
+
+        acc = (acc << 9) | word as u32;
+        acc_bits += 9;
+
+        while acc_bits >= 8 {
+            acc_bits -= 8;
+            packed[packed_len] = (acc >> acc_bits) as u8;
+            packed_len += 1;
+        }
+
+        acc &= (1 << acc_bits) - 1;
+    }
+
+    packed
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::write_packed;
+
+    /// Split a packed stream back into (D/C, byte) words
+    fn unpack(packed: &[u8]) -> Vec<(bool, u8)> {
+        let bits: Vec<bool> = packed
+            .iter()
+            .flat_map(|byte| (0..8).rev().map(move |bit| byte & (1 << bit) != 0))
+            .collect();
+
+        bits.chunks(9)
+            .map(|word| {
+                let byte = word[1..].iter().fold(0, |acc, bit| (acc << 1) | *bit as u8);
+                (word[0], byte)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn words_are_packed_and_padded_with_nops() {
+        let bytes: Vec<u8> = (1..=70).collect();
+        let mut writes = Vec::new();
+
+        write_packed(true, &bytes, |chunk| {
+            writes.push(chunk.to_vec());
+            Ok::<_, ()>(())
+        })
+        .unwrap();
+
+        // 64 bytes fit into the first write, the remaining 6 are padded to a full group
+        assert_eq!(writes.len(), 2);
+        assert_eq!(writes[0].len(), 72);
+        assert_eq!(writes[1].len(), 9);
+
+        let words = unpack(&writes.concat());
+        let expected: Vec<(bool, u8)> = bytes
+            .iter()
+            .map(|byte| (true, *byte))
+            .chain([(false, 0xE3); 2])
+            .collect();
+        assert_eq!(words, expected);
+    }
+}
@@ -12,7 +12,7 @@
 //! ```rust,ignore
 //! let i2c = I2c::i2c1(/* snip */);
 //!
-//! let mut disp: GraphicsMode<_> = Builder::new().connect_i2c(i2c).into();
+//! let mut disp: GraphicsMode<_> = Builder::new().connect_i2c(i2c).unwrap().into();
 //! disp.init();
 //!
 //! disp.set_pixel(10, 20, 1);
@@ -25,6 +25,20 @@
 //! It's possible to customise the driver to suit your display/application. Take a look at the
 //! [Builder] for available options.
 //!
+//! # embedded-hal versions
+//!
+//! The driver uses the embedded-hal 0.2 traits. Enable the `eh1` feature to use embedded-hal 1.0
+//! peripherals as well, by wrapping them in [`Eh1`](eh1/struct.Eh1.html). The feature is additive,
+//! so 0.2 and 1.0 peripherals can be mixed. With an `SpiDevice`, which manages chip select itself,
+//! pass [`NoOutputPin`] as the CS pin of `connect_spi`.
+//!
+//! # Async
+//!
+//! The `async` feature (which implies `eh1`) adds support for `embedded-hal-async` buses. Connect
+//! with `Builder::connect_i2c_async` or `Builder::connect_spi_async`, then use `init_async` and
+//! `flush_async` on [`GraphicsMode`](mode/graphics/struct.GraphicsMode.html). Drawing into the
+//! framebuffer is the same as for blocking interfaces.
+//!
 //! # Examples
 //!
 //! Examples can be found in
@@ -72,7 +86,7 @@
 //!         &mut rcc.apb1,
 //!     );
 //!
-//!     let mut disp: GraphicsMode<_> = Builder::new().connect_i2c(i2c).into();
+//!     let mut disp: GraphicsMode<_> = Builder::new().connect_i2c(i2c).unwrap().into();
 //!
 //!     disp.init().unwrap();
 //!     disp.flush().unwrap();
@@ -96,24 +110,107 @@
 #![deny(unused_import_braces)]
 #![deny(unused_qualifications)]
 
+use core::convert::Infallible;
+
+use crate::config::ConfigError;
+
 /// Errors in this crate
-#[derive(Debug)]
-pub enum Error<CommE, PinE> {
+///
+/// Interfaces without pins of their own, like
+/// [`I2cInterface`](interface/i2c/struct.I2cInterface.html), use the default `Infallible` pin
+/// error. The power sequences of
+/// [`DisplayModeTrait`](mode/displaymode/trait.DisplayModeTrait.html) report the errors of the
+/// reset and VPP pins as `Pin`, so they take pins with the same error type as the interface's.
+///
+/// Bus errors are passed on in `Comm` unchanged, except that an I2C bus wrapped in
+/// [`Eh1`](struct.Eh1.html) reports a missing acknowledge as `DeviceNotResponding`. SPI errors
+/// aren't mapped: SPI has no acknowledge, so a missing display can't be told apart from a working
+/// one, and the remaining embedded-hal 1.0 SPI error kinds (overrun, mode fault, frame format,
+/// chip select fault) are peripheral specific and kept in `Comm`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error<CommE, PinE = Infallible> {
     /// Communication error
     Comm(CommE),
     /// Pin setting error
     Pin(PinE),
+    /// An argument is outside of the range accepted by the display
+    InvalidArgument,
+    /// A coordinate is outside of the display
+    OutOfBounds,
+    /// The display was used before `init`
+    NotInitialised,
+    /// The display didn't acknowledge its address. It may not be connected or powered, or use
+    /// another address.
+    DeviceNotResponding,
+    /// The panel settings sent by `init` are outside of the datasheet ranges, see
+    /// [`PanelConfig::validate`](config/struct.PanelConfig.html#method.validate). The builder
+    /// reports the same problems as a bare [`ConfigError`] before a display is created.
+    Config(ConfigError),
+    /// The display didn't finish an operation in time, e.g. a background flush waited on with
+    /// [`FlushHandle::wait_timeout`](mode/graphics/struct.FlushHandle.html#method.wait_timeout)
+    Timeout,
 }
 
-extern crate embedded_hal as hal;
+/// Errors detected by the driver itself rather than by the bus or a pin
+///
+/// Every [`DisplayInterface`](interface/trait.DisplayInterface.html) error type has to be
+/// convertible from this, so the driver can report them through the interface's error type.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DriverError {
+    /// See [`Error::InvalidArgument`]
+    InvalidArgument,
+    /// See [`Error::OutOfBounds`]
+    OutOfBounds,
+    /// See [`Error::NotInitialised`]
+    NotInitialised,
+    /// See [`Error::DeviceNotResponding`]
+    DeviceNotResponding,
+    /// See [`Error::Config`]
+    Config(ConfigError),
+    /// See [`Error::Timeout`]
+    Timeout,
+}
+
+impl<CommE, PinE> From<DriverError> for Error<CommE, PinE> {
+    fn from(error: DriverError) -> Self {
+        match error {
+            DriverError::InvalidArgument => Error::InvalidArgument,
+            DriverError::OutOfBounds => Error::OutOfBounds,
+            DriverError::NotInitialised => Error::NotInitialised,
+            DriverError::DeviceNotResponding => Error::DeviceNotResponding,
+            DriverError::Config(error) => Error::Config(error),
+            DriverError::Timeout => Error::Timeout,
+        }
+    }
+}
+
+impl From<ConfigError> for DriverError {
+    fn from(error: ConfigError) -> Self {
+        DriverError::Config(error)
+    }
+}
+
+impl<CommE, PinE> From<ConfigError> for Error<CommE, PinE> {
+    fn from(error: ConfigError) -> Self {
+        Error::Config(error)
+    }
+}
 
 pub mod builder;
 mod command;
+pub mod config;
 pub mod displayrotation;
 mod displaysize;
+#[cfg(feature = "eh1")]
+pub mod eh1;
+mod hal;
 pub mod interface;
 pub mod mode;
 pub mod prelude;
 pub mod properties;
 
 pub use crate::builder::{Builder, NoOutputPin};
+#[cfg(feature = "eh1")]
+pub use crate::eh1::Eh1;
@@ -1,35 +1,134 @@
 //! Abstraction of different operating modes for the sh1107
 
-use crate::{interface::DisplayInterface, properties::DisplayProperties};
+use crate::hal::DelayMs;
+use crate::{
+    displaysize::DisplaySizeType,
+    hal::OutputPin,
+    interface::DisplayInterface,
+    properties::{DisplayProperties, PowerState},
+    Error,
+};
 
 /// Display mode abstraction
 pub struct DisplayMode<MODE>(pub MODE);
 
 /// Trait with core functionality for display mode switching
-pub trait DisplayModeTrait<DI> {
+///
+/// # Implementing a mode
+///
+/// Besides `new` and `release`, modes implement `properties` and `properties_mut` to hand out the
+/// [`DisplayProperties`] they own, which the provided power methods use. They have no default,
+/// so modes defined outside of this crate have to add them when upgrading from 0.3:
+///
+/// ```rust,ignore
+/// impl<DI, SIZE> DisplayModeTrait<DI, SIZE> for MyMode<DI, SIZE> {
+///     // `new` and `release` as before
+///
+///     fn properties(&self) -> &DisplayProperties<DI, SIZE> {
+///         &self.properties
+///     }
+///
+///     fn properties_mut(&mut self) -> &mut DisplayProperties<DI, SIZE> {
+///         &mut self.properties
+///     }
+/// }
+/// ```
+pub trait DisplayModeTrait<DI, SIZE> {
     /// Allocate all required data and initialise display for mode
-    fn new(properties: DisplayProperties<DI>) -> Self;
+    fn new(properties: DisplayProperties<DI, SIZE>) -> Self;
 
     /// Release resources for reuse with different mode
-    fn release(self) -> DisplayProperties<DI>;
+    fn release(self) -> DisplayProperties<DI, SIZE>;
+
+    /// Access the display properties shared by all modes
+    fn properties(&self) -> &DisplayProperties<DI, SIZE>;
+
+    /// Mutably access the display properties shared by all modes
+    fn properties_mut(&mut self) -> &mut DisplayProperties<DI, SIZE>;
+
+    /// Get the power state as last set by the driver
+    fn power_state(&self) -> PowerState
+    where
+        SIZE: DisplaySizeType,
+    {
+        self.properties().get_power_state()
+    }
+
+    /// Put the display to sleep to save power. Display RAM is retained, so buffered modes don't
+    /// need to flush after [`wake`](#method.wake). If power was removed with
+    /// [`power_off`](#method.power_off) instead, initialise the mode again and use `flush_all` to
+    /// restore the contents of a buffered mode.
+    fn sleep(&mut self) -> Result<(), DI::Error>
+    where
+        DI: DisplayInterface,
+        SIZE: DisplaySizeType,
+    {
+        self.properties_mut().sleep()
+    }
+
+    /// Wake the display after [`sleep`](#method.sleep)
+    fn wake(&mut self) -> Result<(), DI::Error>
+    where
+        DI: DisplayInterface,
+        SIZE: DisplaySizeType,
+    {
+        self.properties_mut().wake()
+    }
+
+    /// Run the datasheet power up sequence. See
+    /// [`DisplayProperties::power_on`](../../properties/struct.DisplayProperties.html#method.power_on).
+    /// The mode has to be initialised again afterwards.
+    fn power_on<RST, VPP, DELAY, CommE, PinE>(
+        &mut self,
+        rst: &mut RST,
+        vpp: &mut VPP,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<CommE, PinE>>
+    where
+        DI: DisplayInterface<Error = Error<CommE, PinE>>,
+        SIZE: DisplaySizeType,
+        RST: OutputPin<Error = PinE>,
+        VPP: OutputPin<Error = PinE>,
+        DELAY: DelayMs<u8>,
+    {
+        self.properties_mut().power_on(rst, vpp, delay)
+    }
+
+    /// Run the datasheet power down sequence. See
+    /// [`DisplayProperties::power_off`](../../properties/struct.DisplayProperties.html#method.power_off).
+    fn power_off<RST, VPP, DELAY, CommE, PinE>(
+        &mut self,
+        rst: &mut RST,
+        vpp: &mut VPP,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<CommE, PinE>>
+    where
+        DI: DisplayInterface<Error = Error<CommE, PinE>>,
+        SIZE: DisplaySizeType,
+        RST: OutputPin<Error = PinE>,
+        VPP: OutputPin<Error = PinE>,
+        DELAY: DelayMs<u8>,
+    {
+        self.properties_mut().power_off(rst, vpp, delay)
+    }
 }
 
 impl<MODE> DisplayMode<MODE> {
     /// Setup display to run in requested mode
-    pub fn new<DI>(properties: DisplayProperties<DI>) -> Self
+    pub fn new<DI, SIZE>(properties: DisplayProperties<DI, SIZE>) -> Self
     where
-        DI: DisplayInterface,
-        MODE: DisplayModeTrait<DI>,
+        SIZE: DisplaySizeType,
+        MODE: DisplayModeTrait<DI, SIZE>,
     {
         DisplayMode(MODE::new(properties))
     }
 
     /// Change into any mode implementing DisplayModeTrait
     // TODO: Figure out how to stay as generic DisplayMode but act as particular mode
-    pub fn into<DI, NMODE: DisplayModeTrait<DI>>(self) -> NMODE
+    pub fn into<DI, SIZE, NMODE: DisplayModeTrait<DI, SIZE>>(self) -> NMODE
     where
-        DI: DisplayInterface,
-        MODE: DisplayModeTrait<DI>,
+        SIZE: DisplaySizeType,
+        MODE: DisplayModeTrait<DI, SIZE>,
     {
         let properties = self.0.release();
         NMODE::new(properties)
@@ -0,0 +1,281 @@
+//! Double buffered display mode which only sends what changed on the display
+//!
+//! Works like [`GraphicsMode`](../graphics/struct.GraphicsMode.html), but keeps a second copy of
+//! the framebuffer holding what was last sent to the display. On `flush()` the two are compared
+//! byte by byte and only runs of differing bytes are sent, which keeps bus traffic to a minimum
+//! for animations without having to track changed areas by hand. This costs a second framebuffer
+//! worth of RAM.
+//!
+//! ```rust,ignore
+//! let i2c = /* I2C interface from your HAL of choice */;
+//! let mut display: DoubleBufferedGraphicsMode<_> = Builder::new().connect_i2c(i2c).unwrap().into();
+//!
+//! display.init().unwrap();
+//!
+//! for x in 0..128 {
+//!     display.clear();
+//!     display.set_pixel(x, 32, 1);
+//!     // Only the two changed columns are sent
+//!     display.flush().unwrap();
+//! }
+//! ```
+
+use crate::{
+    displayrotation::DisplayRotation,
+    displaysize::{DisplaySize128x64, DisplaySizeType},
+    interface::DisplayInterface,
+    mode::{displaymode::DisplayModeTrait, graphics::GraphicsMode},
+    properties::{AddressMode, DisplayProperties},
+};
+
+/// Double buffered graphics mode handler
+pub struct DoubleBufferedGraphicsMode<DI, SIZE = DisplaySize128x64>
+where
+    DI: DisplayInterface,
+    SIZE: DisplaySizeType,
+{
+    graphics: GraphicsMode<DI, SIZE>,
+    shadow: SIZE::Buffer,
+    shadow_valid: bool,
+}
+
+impl<DI, SIZE> DisplayModeTrait<DI, SIZE> for DoubleBufferedGraphicsMode<DI, SIZE>
+where
+    DI: DisplayInterface,
+    SIZE: DisplaySizeType,
+{
+    /// Create new DoubleBufferedGraphicsMode instance
+    fn new(properties: DisplayProperties<DI, SIZE>) -> Self {
+        DoubleBufferedGraphicsMode {
+            graphics: GraphicsMode::new(properties),
+            shadow: SIZE::new_buffer(),
+            shadow_valid: false,
+        }
+    }
+
+    /// Release all resources used by DoubleBufferedGraphicsMode
+    fn release(self) -> DisplayProperties<DI, SIZE> {
+        self.graphics.release()
+    }
+
+    fn properties(&self) -> &DisplayProperties<DI, SIZE> {
+        self.graphics.properties()
+    }
+
+    fn properties_mut(&mut self) -> &mut DisplayProperties<DI, SIZE> {
+        self.graphics.properties_mut()
+    }
+}
+
+impl<DI, SIZE> DoubleBufferedGraphicsMode<DI, SIZE>
+where
+    DI: DisplayInterface,
+    SIZE: DisplaySizeType,
+{
+    /// Clear the display buffer. You need to call `disp.flush()` for any effect on the screen
+    pub fn clear(&mut self) {
+        self.graphics.clear()
+    }
+
+    /// Send all bytes which differ from what was last sent to the display. The first flush after
+    /// creating or initialising the mode sends the whole framebuffer.
+    pub fn flush(&mut self) -> Result<(), DI::Error> {
+        if !self.shadow_valid {
+            return self.flush_all();
+        }
+
+        self.graphics.prepare_address_mode()?;
+
+        let (display_width, display_height) = SIZE::SIZE.dimensions();
+        let column_offset = SIZE::SIZE.column_offset();
+        let pages = display_height / 8;
+
+        // The framebuffer is split into strips along which the display's address pointer advances:
+        // pages in page addressing mode, columns in vertical addressing mode
+        let address_mode = self.graphics.address_mode();
+        let (strip_len, strips) = match address_mode {
+            AddressMode::Page => (display_width, pages),
+            AddressMode::Vertical => (pages, display_width),
+        };
+
+        let (properties, buffer) = self.graphics.parts_mut();
+        let shadow = self.shadow.as_mut();
+
+        for strip in 0..strips {
+            let base = strip as usize * strip_len as usize;
+            let mut pos = 0;
+
+            while let Some(start) = (pos..strip_len).find(|i| {
+                let idx = base + *i as usize;
+                buffer[idx] != shadow[idx]
+            }) {
+                let end = (start..strip_len)
+                    .find(|i| {
+                        let idx = base + *i as usize;
+                        buffer[idx] == shadow[idx]
+                    })
+                    .unwrap_or(strip_len);
+
+                match address_mode {
+                    AddressMode::Page => properties.set_draw_area(
+                        (column_offset + start, strip * 8),
+                        (column_offset + end, strip * 8 + 8),
+                    )?,
+                    AddressMode::Vertical => properties.set_draw_area(
+                        (column_offset + strip, start * 8),
+                        (column_offset + strip + 1, end * 8),
+                    )?,
+                }
+
+                let run = base + start as usize..base + end as usize;
+                properties.draw(&buffer[run.clone()])?;
+                shadow[run.clone()].copy_from_slice(&buffer[run]);
+
+                pos = end;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write out the whole framebuffer to the display, regardless of what was sent before
+    pub fn flush_all(&mut self) -> Result<(), DI::Error> {
+        self.graphics.flush_all()?;
+
+        let (_, buffer) = self.graphics.parts_mut();
+        self.shadow.as_mut().copy_from_slice(buffer);
+        self.shadow_valid = true;
+
+        Ok(())
+    }
+
+    /// Turn a pixel on or off. A non-zero `value` is treated as on, `0` as off. If the X and Y
+    /// coordinates are out of the bounds of the display, this method call is a noop.
+    pub fn set_pixel(&mut self, x: u32, y: u32, value: u8) {
+        self.graphics.set_pixel(x, y, value)
+    }
+
+    /// Display is set up in column mode, i.e. a byte walks down a column of 8 pixels from
+    /// column 0 on the left, to column _n_ on the right
+    pub fn init(&mut self) -> Result<(), DI::Error> {
+        // Display RAM contents are unknown after initialisation
+        self.shadow_valid = false;
+        self.graphics.init()
+    }
+
+    /// Get display dimensions, taking into account the current rotation of the display
+    pub fn get_dimensions(&self) -> (u8, u8) {
+        self.graphics.get_dimensions()
+    }
+
+    /// Set the display rotation
+    pub fn set_rotation(&mut self, rot: DisplayRotation) -> Result<(), DI::Error> {
+        let address_mode = self.graphics.address_mode();
+        let result = self.graphics.set_rotation(rot);
+
+        // The framebuffer was rearranged for another addressing mode, which the copy of what
+        // was last sent doesn't follow
+        if self.graphics.address_mode() != address_mode {
+            self.shadow_valid = false;
+        }
+
+        result
+    }
+
+    /// Set the display contrast
+    pub fn set_contrast(&mut self, contrast: u8) -> Result<(), DI::Error> {
+        self.graphics.set_contrast(contrast)
+    }
+}
+
+#[cfg(feature = "graphics")]
+use embedded_graphics::{drawable, geometry::Size, pixelcolor::BinaryColor, DrawTarget};
+
+#[cfg(feature = "graphics")]
+impl<DI, SIZE> DrawTarget<BinaryColor> for DoubleBufferedGraphicsMode<DI, SIZE>
+where
+    DI: DisplayInterface,
+    SIZE: DisplaySizeType,
+{
+    type Error = core::convert::Infallible;
+
+    fn draw_pixel(&mut self, pixel: drawable::Pixel<BinaryColor>) -> Result<(), Self::Error> {
+        self.graphics.draw_pixel(pixel)
+    }
+
+    fn size(&self) -> Size {
+        self.graphics.size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DoubleBufferedGraphicsMode;
+    use crate::{
+        displayrotation::DisplayRotation,
+        displaysize::{DisplaySize128x128, DisplaySize128x64},
+        interface::mock::MockInterface,
+        mode::displaymode::DisplayModeTrait,
+        properties::DisplayProperties,
+    };
+
+    #[test]
+    fn sends_differing_runs() {
+        let mut disp: DoubleBufferedGraphicsMode<_, _> = DoubleBufferedGraphicsMode::new(
+            DisplayProperties::new(
+                MockInterface::default(),
+                DisplaySize128x64,
+                DisplayRotation::Rotate0,
+            )
+            .initialised(),
+        );
+
+        disp.flush().unwrap();
+        assert_eq!(
+            disp.graphics.parts_mut().0.iface().data().len(),
+            128 * 64 / 8
+        );
+
+        disp.graphics.parts_mut().0.iface_mut().clear();
+        disp.set_pixel(10, 0, 1);
+        disp.set_pixel(11, 0, 1);
+        disp.set_pixel(100, 63, 1);
+        disp.flush().unwrap();
+        assert_eq!(disp.graphics.parts_mut().0.iface().data(), [1, 1, 1 << 7]);
+
+        disp.graphics.parts_mut().0.iface_mut().clear();
+        disp.flush().unwrap();
+        assert!(disp.graphics.parts_mut().0.iface().transfers.is_empty());
+
+        disp.set_pixel(11, 0, 0);
+        disp.set_pixel(12, 0, 1);
+        disp.flush().unwrap();
+        assert_eq!(disp.graphics.parts_mut().0.iface().data(), [0, 1]);
+    }
+
+    #[test]
+    fn vertical_runs_follow_columns() {
+        let mut disp: DoubleBufferedGraphicsMode<_, _> = DoubleBufferedGraphicsMode::new(
+            DisplayProperties::new(
+                MockInterface::default(),
+                DisplaySize128x128,
+                DisplayRotation::Rotate0,
+            )
+            .initialised(),
+        );
+
+        disp.flush().unwrap();
+        disp.graphics.parts_mut().0.iface_mut().clear();
+
+        disp.set_pixel(7, 0, 1);
+        disp.set_pixel(7, 127, 1);
+        disp.flush().unwrap();
+
+        let (properties, _) = disp.graphics.parts_mut();
+        assert_eq!(properties.iface().data(), [1, 1 << 7]);
+        // The second run starts at the last page of the same column
+        let commands = properties.iface().commands();
+        assert!(commands.iter().any(|c| c[..] == [0xBF]));
+        assert!(commands.iter().all(|c| c[0] & 0xF0 != 0x00 || c[0] == 0x07));
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! ```rust,ignore
 //! let i2c = /* I2C interface from your HAL of choice */;
-//! let display: GraphicsMode<_> = Builder::new().connect_i2c(i2c).into();
+//! let display: GraphicsMode<_> = Builder::new().connect_i2c(i2c).unwrap().into();
 //! let image = include_bytes!("image_16x16.raw");
 //!
 //! display.init().unwrap();
@@ -14,59 +14,177 @@
 //! display.draw(Font6x8::render_str("Hello Rust!", 1u8.into()).translate(Coord::new(24, 24)).into_iter());
 //! display.flush().unwrap();
 //! ```
+//!
+//! With the `async` feature, a display connected with
+//! [`Builder::connect_i2c_async`](../../builder/struct.Builder.html#method.connect_i2c_async) or
+//! [`Builder::connect_spi_async`](../../builder/struct.Builder.html#method.connect_spi_async) is
+//! initialised and flushed with `init_async` and `flush_async` instead:
+//!
+//! ```rust,ignore
+//! let mut display: GraphicsMode<_> = Builder::new().connect_i2c_async(i2c).unwrap().into();
+//!
+//! display.init_async().await.unwrap();
+//! display.set_pixel(10, 20, 1);
+//! display.flush_async().await.unwrap();
+//! ```
 
-use hal::{blocking::delay::DelayMs, digital::v2::OutputPin};
-
+use crate::hal::DelayMs;
+#[cfg(feature = "async")]
+use crate::interface::AsyncDisplayInterface;
 use crate::{
-    displayrotation::DisplayRotation, interface::DisplayInterface,
-    mode::displaymode::DisplayModeTrait, properties::DisplayProperties, Error,
+    displayrotation::DisplayRotation,
+    displaysize::{DisplaySize128x64, DisplaySizeType},
+    hal::OutputPin,
+    interface::{DisplayInterface, DmaDisplayInterface},
+    mode::displaymode::DisplayModeTrait,
+    properties::{AddressMode, DisplayProperties},
+    DriverError, Error,
 };
 
-const BUFFER_SIZE: usize = 132 * 64 / 8;
+/// Number of pages in SH1107 display RAM
+const RAM_PAGES: usize = 16;
+
+/// Range of columns in a page which changed since the last flush
+#[derive(Clone, Copy)]
+struct DirtySpan {
+    start: u8,
+    end: u8,
+}
+
+impl DirtySpan {
+    const CLEAN: DirtySpan = DirtySpan {
+        start: u8::MAX,
+        end: 0,
+    };
+
+    fn is_clean(self) -> bool {
+        self.start >= self.end
+    }
+
+    fn include(&mut self, column: u8) {
+        self.start = self.start.min(column);
+        self.end = self.end.max(column + 1);
+    }
+}
+
+/// Area of display RAM sent by a flush, and where its bytes are in the framebuffer. The bytes are
+/// `count` chunks of `len` bytes, `stride` bytes apart, starting at `offset`.
+#[derive(Clone, Copy)]
+struct Strip {
+    start: (u8, u8),
+    end: (u8, u8),
+    offset: usize,
+    len: usize,
+    stride: usize,
+    count: usize,
+}
+
+impl Strip {
+    fn chunks(self, buffer: &[u8]) -> impl Iterator<Item = &[u8]> {
+        (0..self.count).map(move |chunk| {
+            let start = self.offset + chunk * self.stride;
+            &buffer[start..start + self.len]
+        })
+    }
+}
+
+/// Transpose a framebuffer in place between page and vertical layout, where `strips` is the
+/// number of pages (page layout) or columns (vertical layout) it currently holds. Each element is
+/// moved along its cycle of the permutation, starting from the smallest index of the cycle.
+fn transpose(buffer: &mut [u8], strips: usize) {
+    let last = buffer.len().saturating_sub(1);
+    let next = |idx: usize| idx * strips % last;
+
+    for start in 1..last {
+        let mut idx = next(start);
+        while idx > start {
+            idx = next(idx);
+        }
+        if idx < start {
+            continue;
+        }
+
+        let mut carry = buffer[start];
+        loop {
+            idx = next(idx);
+            core::mem::swap(&mut carry, &mut buffer[idx]);
+            if idx == start {
+                break;
+            }
+        }
+    }
+}
 
 /// Graphics mode handler
-pub struct GraphicsMode<DI>
+///
+/// The framebuffer is sized by `SIZE`, so a 128x32 panel only uses 512 bytes of RAM while a
+/// 128x128 panel gets the full 2048 bytes.
+///
+/// Changes to the framebuffer are tracked per page, so [`flush`](#method.flush) only sends the
+/// parts of the display which changed since the previous flush.
+pub struct GraphicsMode<DI, SIZE = DisplaySize128x64>
 where
-    DI: DisplayInterface,
+    SIZE: DisplaySizeType,
 {
-    properties: DisplayProperties<DI>,
-    buffer: [u8; BUFFER_SIZE],
+    properties: DisplayProperties<DI, SIZE>,
+    buffer: SIZE::Buffer,
+    dirty: [DirtySpan; RAM_PAGES],
+    layout: AddressMode,
 }
 
-impl<DI> DisplayModeTrait<DI> for GraphicsMode<DI>
+impl<DI, SIZE> DisplayModeTrait<DI, SIZE> for GraphicsMode<DI, SIZE>
 where
-    DI: DisplayInterface,
+    SIZE: DisplaySizeType,
 {
     /// Create new GraphicsMode instance
-    fn new(properties: DisplayProperties<DI>) -> Self {
-        GraphicsMode {
+    fn new(properties: DisplayProperties<DI, SIZE>) -> Self {
+        let mut mode = GraphicsMode {
+            layout: Self::layout_for(properties.get_rotation()),
             properties,
-            buffer: [0; BUFFER_SIZE],
-        }
+            buffer: SIZE::new_buffer(),
+            dirty: [DirtySpan::CLEAN; RAM_PAGES],
+        };
+
+        // Display RAM contents are unknown, so the first flush must send everything
+        mode.mark_all_dirty();
+
+        mode
     }
 
     /// Release all resources used by GraphicsMode
-    fn release(self) -> DisplayProperties<DI> {
+    fn release(self) -> DisplayProperties<DI, SIZE> {
         self.properties
     }
+
+    fn properties(&self) -> &DisplayProperties<DI, SIZE> {
+        &self.properties
+    }
+
+    fn properties_mut(&mut self) -> &mut DisplayProperties<DI, SIZE> {
+        &mut self.properties
+    }
 }
 
-impl<DI> GraphicsMode<DI>
+impl<DI, SIZE> GraphicsMode<DI, SIZE>
 where
-    DI: DisplayInterface,
+    SIZE: DisplaySizeType,
 {
     /// Clear the display buffer. You need to call `disp.flush()` for any effect on the screen
     pub fn clear(&mut self) {
-        self.buffer = [0; BUFFER_SIZE];
+        self.buffer = SIZE::new_buffer();
+        self.mark_all_dirty();
     }
 
-    /// Reset display
-    pub fn reset<RST, DELAY, PinE>(
+    /// Reset display. Pin errors are reported through the interface's [`Error`], like for
+    /// [`DisplayModeTrait::power_on`](../displaymode/trait.DisplayModeTrait.html#method.power_on).
+    #[deprecated(note = "use `DisplayModeTrait::power_on`, which also handles VPP")]
+    pub fn reset<RST, DELAY, CommE, PinE>(
         &mut self,
         rst: &mut RST,
         delay: &mut DELAY,
-    ) -> Result<(), Error<(), PinE>>
+    ) -> Result<(), Error<CommE, PinE>>
     where
+        DI: DisplayInterface<Error = Error<CommE, PinE>>,
         RST: OutputPin<Error = PinE>,
         DELAY: DelayMs<u8>,
     {
@@ -77,87 +195,240 @@ where
         rst.set_high().map_err(Error::Pin)
     }
 
-    /// Write out data to display
-    pub fn flush(&mut self) -> Result<(), DI::Error> {
-        let display_size = self.properties.get_size();
+    /// Areas of the framebuffer which changed since the last flush
+    fn dirty_strips(&self) -> [Option<Strip>; RAM_PAGES] {
+        let (display_width, display_height) = SIZE::SIZE.dimensions();
+        let column_offset = SIZE::SIZE.column_offset();
+        let pages = display_height as usize / 8;
+        let mut strips = [None; RAM_PAGES];
+
+        match self.layout {
+            // Each dirty page is sent as its own strip
+            AddressMode::Page => {
+                for (page, span) in self.dirty[..pages].iter().enumerate() {
+                    if span.is_clean() {
+                        continue;
+                    }
+
+                    strips[page] = Some(Strip {
+                        start: (column_offset + span.start, page as u8 * 8),
+                        end: (column_offset + span.end, page as u8 * 8 + 8),
+                        offset: page * display_width as usize + span.start as usize,
+                        len: (span.end - span.start) as usize,
+                        stride: 0,
+                        count: 1,
+                    });
+                }
+            }
+            // The framebuffer is stored column by column, so the bounding box of all changes is
+            // sent as a single rectangle
+            AddressMode::Vertical => {
+                let mut bounds = None;
+                for (page, span) in self.dirty[..pages].iter().enumerate() {
+                    if span.is_clean() {
+                        continue;
+                    }
+
+                    bounds = Some(match bounds {
+                        None => (page, page, span.start, span.end),
+                        Some((first, _, start, end)) => {
+                            (first, page, span.start.min(start), span.end.max(end))
+                        }
+                    });
+                }
 
-        // Ensure the display buffer is at the origin of the display before we send the full frame
-        // to prevent accidental offsets
-        let (display_width, display_height) = display_size.dimensions();
-        let column_offset = display_size.column_offset();
-        self.properties.set_draw_area(
-            (column_offset, 0),
-            (display_width + column_offset, display_height),
-        )?;
+                if let Some((first_page, last_page, start, end)) = bounds {
+                    strips[0] = Some(Strip {
+                        start: (column_offset + start, first_page as u8 * 8),
+                        end: (column_offset + end, last_page as u8 * 8 + 8),
+                        offset: start as usize * pages + first_page,
+                        len: last_page - first_page + 1,
+                        stride: pages,
+                        count: (end - start) as usize,
+                    });
+                }
+            }
+        }
+
+        strips
+    }
 
-        let length = (display_width as usize) * (display_height as usize) / 8;
+    /// The whole framebuffer as a single strip
+    fn full_strip(&self) -> Strip {
+        let (display_width, display_height) = SIZE::SIZE.dimensions();
+        let column_offset = SIZE::SIZE.column_offset();
+
+        Strip {
+            start: (column_offset, 0),
+            end: (display_width + column_offset, display_height),
+            offset: 0,
+            len: self.buffer.as_ref().len(),
+            stride: 0,
+            count: 1,
+        }
+    }
 
-        self.properties.draw(&self.buffer[..length])
+    /// Display properties and framebuffer, for modes building on top of `GraphicsMode`
+    pub(crate) fn parts_mut(&mut self) -> (&mut DisplayProperties<DI, SIZE>, &[u8]) {
+        (&mut self.properties, self.buffer.as_ref())
+    }
+
+    /// Mark the whole framebuffer as changed
+    fn mark_all_dirty(&mut self) {
+        let (display_width, _) = SIZE::SIZE.dimensions();
+
+        self.dirty = [DirtySpan {
+            start: 0,
+            end: display_width,
+        }; RAM_PAGES];
     }
 
     /// Turn a pixel on or off. A non-zero `value` is treated as on, `0` as off. If the X and Y
     /// coordinates are out of the bounds of the display, this method call is a noop.
     pub fn set_pixel(&mut self, x: u32, y: u32, value: u8) {
-        let (display_width, _) = self.properties.get_size().dimensions();
-        let display_rotation = self.properties.get_rotation();
+        let (display_width, display_height) = self.properties.get_size().dimensions();
 
-        let idx = match display_rotation {
-            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => {
-                if x >= display_width as u32 {
-                    return;
-                }
-                ((y as usize) / 8 * display_width as usize) + (x as usize)
-            }
-
-            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => {
-                if y >= display_width as u32 {
-                    return;
-                }
-                ((x as usize) / 8 * display_width as usize) + (y as usize)
-            }
+        // Rotation by 90 or 270 degrees swaps the roles of X and Y in display RAM
+        let (column, row) = match self.properties.get_rotation() {
+            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => (x, y),
+            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => (y, x),
         };
 
-        if idx >= self.buffer.len() {
+        if column >= display_width as u32 || row >= display_height as u32 {
             return;
         }
 
-        let (byte, bit) = match display_rotation {
-            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => {
-                let byte =
-                    &mut self.buffer[((y as usize) / 8 * display_width as usize) + (x as usize)];
-                let bit = 1 << (y % 8);
-
-                (byte, bit)
-            }
-            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => {
-                let byte =
-                    &mut self.buffer[((x as usize) / 8 * display_width as usize) + (y as usize)];
-                let bit = 1 << (x % 8);
-
-                (byte, bit)
-            }
+        let (column, page) = (column as usize, row as usize / 8);
+        let idx = match self.layout {
+            AddressMode::Page => page * display_width as usize + column,
+            AddressMode::Vertical => column * (display_height as usize / 8) + page,
         };
 
+        let byte = &mut self.buffer.as_mut()[idx];
+        let bit = 1 << (row % 8);
+        let old = *byte;
+
         if value == 0 {
             *byte &= !bit;
         } else {
             *byte |= bit;
         }
+
+        if *byte != old {
+            self.dirty[page].include(column as u8);
+        }
     }
 
-    /// Display is set up in column mode, i.e. a byte walks down a column of 8 pixels from
-    /// column 0 on the left, to column _n_ on the right
-    pub fn init(&mut self) -> Result<(), DI::Error> {
-        self.properties.init_column_mode()
+    /// Addressing mode used to send the framebuffer, which also determines its layout
+    pub(crate) fn address_mode(&self) -> AddressMode {
+        self.layout
+    }
+
+    /// Addressing mode sending a frame with the given rotation with the fewest address commands.
+    /// Rotated by 90 or 270 degrees, the rows of the image run down display RAM columns, so a
+    /// changed row is sent as a single column in vertical addressing mode, where page addressing
+    /// would need one address command per page. Panels spanning all 128 rows of display RAM
+    /// (128x128 and 64x128) stream a full frame in vertical addressing mode with a single address
+    /// command regardless of rotation.
+    fn layout_for(rotation: DisplayRotation) -> AddressMode {
+        let (_, display_height) = SIZE::SIZE.dimensions();
+
+        match rotation {
+            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => AddressMode::Vertical,
+            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 if display_height == 128 => {
+                AddressMode::Vertical
+            }
+            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => AddressMode::Page,
+        }
+    }
+
+    /// Rearrange the framebuffer for another addressing mode. Changed areas are tracked in display
+    /// RAM coordinates, so they stay valid.
+    fn set_layout(&mut self, layout: AddressMode) {
+        if layout == self.layout {
+            return;
+        }
+
+        let (display_width, display_height) = SIZE::SIZE.dimensions();
+        let strips = match self.layout {
+            AddressMode::Page => display_height as usize / 8,
+            AddressMode::Vertical => display_width as usize,
+        };
+
+        transpose(self.buffer.as_mut(), strips);
+        self.layout = layout;
     }
 
     /// Get display dimensions, taking into account the current rotation of the display
     pub fn get_dimensions(&self) -> (u8, u8) {
         self.properties.get_dimensions()
     }
+}
 
-    /// Set the display rotation
+impl<DI, SIZE> GraphicsMode<DI, SIZE>
+where
+    DI: DisplayInterface,
+    SIZE: DisplaySizeType,
+{
+    /// Write out the parts of the framebuffer which changed since the last flush to the display
+    pub fn flush(&mut self) -> Result<(), DI::Error> {
+        self.prepare_address_mode()?;
+
+        for strip in self.dirty_strips().iter().flatten() {
+            self.send_strip(*strip)?;
+        }
+
+        self.dirty = [DirtySpan::CLEAN; RAM_PAGES];
+
+        Ok(())
+    }
+
+    /// Write out the whole framebuffer to the display, regardless of what changed since the last
+    /// flush
+    pub fn flush_all(&mut self) -> Result<(), DI::Error> {
+        self.prepare_address_mode()?;
+
+        // Ensure the display buffer is at the origin of the display before we send the full frame
+        // to prevent accidental offsets
+        self.send_strip(self.full_strip())?;
+
+        self.dirty = [DirtySpan::CLEAN; RAM_PAGES];
+
+        Ok(())
+    }
+
+    fn send_strip(&mut self, strip: Strip) -> Result<(), DI::Error> {
+        self.properties.set_draw_area(strip.start, strip.end)?;
+
+        for chunk in strip.chunks(self.buffer.as_ref()) {
+            self.properties.draw(chunk)?;
+        }
+
+        Ok(())
+    }
+
+    /// Switch the display to the addressing mode matching the framebuffer layout
+    pub(crate) fn prepare_address_mode(&mut self) -> Result<(), DI::Error> {
+        let address_mode = self.layout;
+
+        if self.properties.get_address_mode() != address_mode {
+            self.properties.set_address_mode(address_mode)?;
+        }
+
+        Ok(())
+    }
+
+    /// Display is set up in column mode, i.e. a byte walks down a column of 8 pixels from
+    /// column 0 on the left, to column _n_ on the right
+    pub fn init(&mut self) -> Result<(), DI::Error> {
+        self.mark_all_dirty();
+        self.properties.init_column_mode()
+    }
+
+    /// Set the display rotation. The framebuffer is kept, and switches to the addressing mode
+    /// which suits the new rotation best.
     pub fn set_rotation(&mut self, rot: DisplayRotation) -> Result<(), DI::Error> {
+        self.set_layout(Self::layout_for(rot));
         self.properties.set_rotation(rot)
     }
 
@@ -167,6 +438,201 @@ where
     }
 }
 
+impl<DI, SIZE> GraphicsMode<DI, SIZE>
+where
+    DI: DmaDisplayInterface,
+    SIZE: DisplaySizeType,
+{
+    /// Start writing out the parts of the framebuffer which changed since the last flush in the
+    /// background, e.g. using DMA. Returns a [`FlushHandle`] which has to be polled to completion,
+    /// and which keeps the framebuffer borrowed until then.
+    ///
+    /// ```rust,ignore
+    /// // Safety: the handle is dropped at the end of the block
+    /// let mut flush = unsafe { display.flush_dma() }?;
+    /// while let Err(nb::Error::WouldBlock) = flush.poll() {
+    ///     // Do other work while the frame is sent
+    /// }
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// The transfer keeps reading the framebuffer after the call which started it returned, and
+    /// only the handle's `Drop` waits for it to finish. The handle must therefore not be leaked,
+    /// e.g. with `mem::forget` or a reference counting cycle, as the framebuffer could then be
+    /// changed, moved or dropped while it is still being sent.
+    #[allow(unsafe_code)]
+    pub unsafe fn flush_dma(&mut self) -> Result<FlushHandle<'_, DI, SIZE>, DI::Error> {
+        self.prepare_address_mode()?;
+
+        Ok(FlushHandle {
+            strips: self.dirty_strips(),
+            mode: self,
+            strip: 0,
+            chunk: 0,
+            position: 0,
+            area_set: false,
+            in_flight: None,
+        })
+    }
+}
+
+/// A background flush started by [`GraphicsMode::flush_dma`](struct.GraphicsMode.html#method.flush_dma)
+///
+/// Each call to [`poll`](#method.poll) checks whether the current transfer finished, and if so
+/// starts the next one. Display RAM is addressed in between with short blocking command writes.
+/// Dropping the handle before the flush completed waits for the current transfer to finish and
+/// leaves the remaining changes for the next flush. The handle must not be leaked, see the safety
+/// section of `flush_dma`.
+pub struct FlushHandle<'a, DI, SIZE>
+where
+    DI: DmaDisplayInterface,
+    SIZE: DisplaySizeType,
+{
+    mode: &'a mut GraphicsMode<DI, SIZE>,
+    strips: [Option<Strip>; RAM_PAGES],
+    strip: usize,
+    chunk: usize,
+    position: usize,
+    area_set: bool,
+    in_flight: Option<usize>,
+}
+
+impl<DI, SIZE> FlushHandle<'_, DI, SIZE>
+where
+    DI: DmaDisplayInterface,
+    SIZE: DisplaySizeType,
+{
+    /// Advance the flush. Returns `Ok(())` once everything was sent, or `WouldBlock` while a
+    /// transfer is still running.
+    pub fn poll(&mut self) -> nb::Result<(), DI::Error> {
+        loop {
+            if let Some(count) = self.in_flight {
+                self.mode.properties.poll_draw(count)?;
+                self.in_flight = None;
+                self.position += count;
+            }
+
+            let strip = match self.strips.get(self.strip) {
+                Some(Some(strip)) => *strip,
+                Some(None) => {
+                    self.strip += 1;
+                    continue;
+                }
+                None => {
+                    self.mode.dirty = [DirtySpan::CLEAN; RAM_PAGES];
+                    return Ok(());
+                }
+            };
+
+            if self.chunk == strip.count {
+                self.strip += 1;
+                self.chunk = 0;
+                self.area_set = false;
+                continue;
+            }
+
+            if !self.area_set {
+                self.mode.properties.set_draw_area(strip.start, strip.end)?;
+                self.area_set = true;
+            }
+
+            let chunk = match strip.chunks(self.mode.buffer.as_ref()).nth(self.chunk) {
+                Some(chunk) if self.position < chunk.len() => &chunk[self.position..],
+                _ => {
+                    self.chunk += 1;
+                    self.position = 0;
+                    continue;
+                }
+            };
+
+            self.in_flight = Some(self.mode.properties.start_draw(chunk)?);
+        }
+    }
+
+    /// Block until the flush completed
+    pub fn wait(mut self) -> Result<(), DI::Error> {
+        nb::block!(self.poll())
+    }
+
+    /// Block until the flush completed, polling at most `max_polls` times. Fails with `Timeout`
+    /// if it is still running then, leaving the handle to be polled again. Note that dropping the
+    /// handle still waits for the current transfer to finish.
+    pub fn wait_timeout(&mut self, max_polls: u32) -> Result<(), DI::Error> {
+        for _ in 0..max_polls {
+            match self.poll() {
+                Err(nb::Error::WouldBlock) => {}
+                Err(nb::Error::Other(error)) => return Err(error),
+                Ok(()) => return Ok(()),
+            }
+        }
+
+        Err(DriverError::Timeout.into())
+    }
+}
+
+impl<DI, SIZE> Drop for FlushHandle<'_, DI, SIZE>
+where
+    DI: DmaDisplayInterface,
+    SIZE: DisplaySizeType,
+{
+    fn drop(&mut self) {
+        // The framebuffer must not be released while it is still being read. Errors can't be
+        // reported here, and the remaining changes are left for the next flush either way.
+        if let Some(count) = self.in_flight.take() {
+            let _ = nb::block!(self.mode.properties.poll_draw(count));
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<DI, SIZE> GraphicsMode<DI, SIZE>
+where
+    DI: AsyncDisplayInterface,
+    SIZE: DisplaySizeType,
+{
+    /// Asynchronous version of [`flush`](#method.flush)
+    pub async fn flush_async(&mut self) -> Result<(), DI::Error> {
+        self.prepare_address_mode_async().await?;
+
+        for strip in self.dirty_strips().iter().flatten() {
+            self.send_strip_async(*strip).await?;
+        }
+
+        self.dirty = [DirtySpan::CLEAN; RAM_PAGES];
+
+        Ok(())
+    }
+
+    async fn send_strip_async(&mut self, strip: Strip) -> Result<(), DI::Error> {
+        self.properties
+            .set_draw_area_async(strip.start, strip.end)
+            .await?;
+
+        for chunk in strip.chunks(self.buffer.as_ref()) {
+            self.properties.draw_async(chunk).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn prepare_address_mode_async(&mut self) -> Result<(), DI::Error> {
+        let address_mode = self.layout;
+
+        if self.properties.get_address_mode() != address_mode {
+            self.properties.set_address_mode_async(address_mode).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Asynchronous version of [`init`](#method.init)
+    pub async fn init_async(&mut self) -> Result<(), DI::Error> {
+        self.mark_all_dirty();
+        self.properties.init_column_mode_async().await
+    }
+}
+
 #[cfg(feature = "graphics")]
 use embedded_graphics::{
     drawable,
@@ -178,12 +644,14 @@ use embedded_graphics::{
     DrawTarget,
 };
 
+/// Drawing only changes the framebuffer, which can't fail. Nothing is sent to the display until
+/// the next flush.
 #[cfg(feature = "graphics")]
-impl<DI> DrawTarget<BinaryColor> for GraphicsMode<DI>
+impl<DI, SIZE> DrawTarget<BinaryColor> for GraphicsMode<DI, SIZE>
 where
-    DI: DisplayInterface,
+    SIZE: DisplaySizeType,
 {
-    type Error = DI::Error;
+    type Error = core::convert::Infallible;
 
     fn draw_pixel(&mut self, pixel: drawable::Pixel<BinaryColor>) -> Result<(), Self::Error> {
         let drawable::Pixel(pos, color) = pixel;
@@ -206,3 +674,298 @@ where
         Size::new(w as u32, h as u32)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::GraphicsMode;
+    use crate::{
+        displayrotation::DisplayRotation,
+        displaysize::{DisplaySize128x128, DisplaySize128x64},
+        interface::mock::MockInterface,
+        mode::displaymode::DisplayModeTrait,
+        properties::{AddressMode, DisplayProperties},
+        Error,
+    };
+
+    crate::custom_display_size!(DisplaySize96x96, 96, 96, 16, 16);
+
+    #[test]
+    fn custom_size_uses_window_of_display_ram() {
+        let mut disp: GraphicsMode<_, _> = GraphicsMode::new(
+            DisplayProperties::new(
+                MockInterface::default(),
+                DisplaySize96x96,
+                DisplayRotation::Rotate90,
+            )
+            .initialised(),
+        );
+
+        disp.init().unwrap();
+        disp.flush().unwrap();
+        disp.properties.iface_mut().clear();
+        disp.set_pixel(9, 5, 1);
+        disp.flush().unwrap();
+
+        let props = disp.release();
+        let commands = props.iface().commands();
+        // Page 1, column 16 + 5
+        assert_eq!(commands[..3], [[0xB1], [0x05], [0x11]]);
+        assert_eq!(props.get_panel_config().display_offset, 16);
+        assert_eq!(props.get_dimensions(), (96, 96));
+    }
+
+    #[test]
+    fn page_layout() {
+        let mut disp: GraphicsMode<_, _> = GraphicsMode::new(
+            DisplayProperties::new(
+                MockInterface::default(),
+                DisplaySize128x64,
+                DisplayRotation::Rotate0,
+            )
+            .initialised(),
+        );
+
+        disp.set_pixel(5, 9, 1);
+        disp.flush().unwrap();
+
+        let props = disp.release();
+        assert_eq!(props.get_address_mode(), AddressMode::Page);
+
+        let data = props.iface().data();
+        assert_eq!(data.len(), 128 * 64 / 8);
+        // Page 1, column 5
+        assert_eq!(data[128 + 5], 1 << 1);
+    }
+
+    #[test]
+    fn vertical_layout_for_full_height_panels() {
+        let mut disp: GraphicsMode<_, _> = GraphicsMode::new(
+            DisplayProperties::new(
+                MockInterface::default(),
+                DisplaySize128x128,
+                DisplayRotation::Rotate0,
+            )
+            .initialised(),
+        );
+
+        disp.set_pixel(5, 9, 1);
+        disp.set_pixel(127, 127, 1);
+        disp.flush().unwrap();
+
+        assert_eq!(disp.properties.get_address_mode(), AddressMode::Vertical);
+
+        let data = disp.release().iface().data();
+        assert_eq!(data.len(), 128 * 128 / 8);
+        // Column 5, page 1
+        assert_eq!(data[5 * 16 + 1], 1 << 1);
+        // Last column, last page
+        assert_eq!(data[127 * 16 + 15], 1 << 7);
+        assert_eq!(data.iter().filter(|b| **b != 0).count(), 2);
+    }
+
+    #[test]
+    fn vertical_layout_for_rotated_panels() {
+        let mut disp: GraphicsMode<_, _> = GraphicsMode::new(
+            DisplayProperties::new(
+                MockInterface::default(),
+                DisplaySize128x64,
+                DisplayRotation::Rotate90,
+            )
+            .initialised(),
+        );
+        disp.flush().unwrap();
+        assert_eq!(disp.properties.get_address_mode(), AddressMode::Vertical);
+        disp.properties.iface_mut().clear();
+
+        // A row of the rotated image is a column of display RAM, sent with one address command
+        for x in 0..64 {
+            disp.set_pixel(x, 3, 1);
+        }
+        disp.flush().unwrap();
+
+        let iface = disp.properties.iface();
+        // Page 0, column 3
+        assert_eq!(iface.commands()[..3], [[0xB0], [0x03], [0x10]]);
+        assert_eq!(iface.data(), [0xFF; 8]);
+    }
+
+    #[test]
+    fn rotation_keeps_framebuffer_contents() {
+        let mut disp: GraphicsMode<_, _> = GraphicsMode::new(
+            DisplayProperties::new(
+                MockInterface::default(),
+                DisplaySize128x64,
+                DisplayRotation::Rotate0,
+            )
+            .initialised(),
+        );
+        disp.set_pixel(5, 9, 1);
+        disp.set_pixel(127, 63, 1);
+
+        disp.set_rotation(DisplayRotation::Rotate270).unwrap();
+        assert_eq!(disp.address_mode(), AddressMode::Vertical);
+        disp.flush_all().unwrap();
+
+        let data = disp.properties.iface().data();
+        // Column 5, page 1 and the last column, last page
+        assert_eq!(data[5 * 8 + 1], 1 << 1);
+        assert_eq!(data[127 * 8 + 7], 1 << 7);
+        assert_eq!(data.iter().filter(|b| **b != 0).count(), 2);
+
+        disp.set_rotation(DisplayRotation::Rotate0).unwrap();
+        disp.properties.iface_mut().clear();
+        disp.flush_all().unwrap();
+
+        let data = disp.properties.iface().data();
+        assert_eq!(data[128 + 5], 1 << 1);
+        assert_eq!(data[7 * 128 + 127], 1 << 7);
+        assert_eq!(data.iter().filter(|b| **b != 0).count(), 2);
+    }
+
+    #[test]
+    fn flush_sends_only_dirty_spans() {
+        let mut disp: GraphicsMode<_, _> = GraphicsMode::new(
+            DisplayProperties::new(
+                MockInterface::default(),
+                DisplaySize128x64,
+                DisplayRotation::Rotate0,
+            )
+            .initialised(),
+        );
+        disp.flush().unwrap();
+        disp.properties.iface_mut().clear();
+
+        disp.set_pixel(10, 20, 1);
+        disp.set_pixel(12, 21, 1);
+        disp.set_pixel(40, 60, 1);
+        // Setting a pixel to its current value is not a change
+        disp.set_pixel(90, 0, 0);
+        disp.flush().unwrap();
+
+        assert_eq!(disp.properties.iface().data(), [1 << 4, 0, 1 << 5, 1 << 4]);
+
+        disp.properties.iface_mut().clear();
+        disp.flush().unwrap();
+        assert!(disp.properties.iface().transfers.is_empty());
+
+        disp.flush_all().unwrap();
+        assert_eq!(disp.properties.iface().data().len(), 128 * 64 / 8);
+    }
+
+    #[test]
+    fn vertical_flush_sends_dirty_bounding_box() {
+        let mut disp: GraphicsMode<_, _> = GraphicsMode::new(
+            DisplayProperties::new(
+                MockInterface::default(),
+                DisplaySize128x128,
+                DisplayRotation::Rotate0,
+            )
+            .initialised(),
+        );
+        disp.flush().unwrap();
+        disp.properties.iface_mut().clear();
+
+        disp.set_pixel(3, 8, 1);
+        disp.set_pixel(4, 31, 1);
+        disp.flush().unwrap();
+
+        // Columns 3 and 4, pages 1 to 3 each
+        assert_eq!(disp.properties.iface().data(), [1, 0, 0, 0, 0, 1 << 7]);
+    }
+
+    #[test]
+    #[allow(unsafe_code)]
+    fn dma_flush_matches_blocking() {
+        let new_display = || -> GraphicsMode<_, _> {
+            let mut disp = GraphicsMode::new(
+                DisplayProperties::new(
+                    MockInterface::default(),
+                    DisplaySize128x64,
+                    DisplayRotation::Rotate0,
+                )
+                .initialised(),
+            );
+            disp.flush_all().unwrap();
+            disp.parts_mut().0.iface_mut().clear();
+            disp
+        };
+
+        let mut blocking = new_display();
+        let mut dma = new_display();
+
+        for disp in [&mut blocking, &mut dma] {
+            disp.set_pixel(3, 12, 1);
+            disp.set_pixel(100, 50, 1);
+        }
+
+        blocking.flush().unwrap();
+
+        // Safety: the handle is dropped below
+        let mut flush = unsafe { dma.flush_dma() }.unwrap();
+        let mut polls = 0;
+        while let Err(nb::Error::WouldBlock) = flush.poll() {
+            polls += 1;
+        }
+        drop(flush);
+
+        // One pending poll for each of the two dirty pages
+        assert_eq!(polls, 2);
+        assert_eq!(
+            blocking.release().iface().transfers,
+            dma.release().iface().transfers
+        );
+    }
+
+    #[test]
+    #[allow(unsafe_code)]
+    fn dma_flush_times_out() {
+        let mut disp: GraphicsMode<_, _> = GraphicsMode::new(
+            DisplayProperties::new(
+                MockInterface::default(),
+                DisplaySize128x64,
+                DisplayRotation::Rotate0,
+            )
+            .initialised(),
+        );
+        disp.set_pixel(3, 12, 1);
+        disp.set_pixel(100, 50, 1);
+
+        // Safety: the handle is dropped at the end of the test
+        let mut flush = unsafe { disp.flush_dma() }.unwrap();
+        assert_eq!(flush.wait_timeout(1), Err(Error::Timeout));
+        assert_eq!(flush.wait_timeout(100), Ok(()));
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn async_init_and_flush_match_blocking() {
+        use crate::interface::mock::block_on;
+
+        let new_display = || -> GraphicsMode<_, _> {
+            GraphicsMode::new(DisplayProperties::new(
+                MockInterface::default(),
+                DisplaySize128x128,
+                DisplayRotation::Rotate0,
+            ))
+        };
+
+        let mut blocking = new_display();
+        let mut asynchronous = new_display();
+
+        blocking.init().unwrap();
+        block_on(asynchronous.init_async()).unwrap();
+
+        for disp in [&mut blocking, &mut asynchronous] {
+            disp.set_pixel(3, 40, 1);
+            disp.set_pixel(90, 70, 1);
+        }
+
+        blocking.flush().unwrap();
+        block_on(asynchronous.flush_async()).unwrap();
+
+        assert_eq!(
+            blocking.release().iface().transfers,
+            asynchronous.release().iface().transfers
+        );
+    }
+}
@@ -4,7 +4,14 @@
 //! methods it exposes. Look at the modes below for more information on what they expose.
 
 pub mod displaymode;
+pub mod doublebuffered;
+mod font8x8;
 pub mod graphics;
 pub mod raw;
+pub mod terminal;
+pub mod unbuffered;
 
-pub use self::{graphics::GraphicsMode, raw::RawMode};
+pub use self::{
+    doublebuffered::DoubleBufferedGraphicsMode, graphics::GraphicsMode, raw::RawMode,
+    terminal::TerminalMode, unbuffered::UnbufferedGraphicsMode,
+};
@@ -1,39 +1,200 @@
-//! Raw mode for coercion into richer driver types
+//! Raw mode for coercion into richer driver types, or direct access to display RAM
 //!
-//! A display driver instance without high level functionality used as a return type from the
-//! builder. Used as a source to coerce the driver into richer modes like
-//! [`GraphicsMode`](../graphics/index.html).
+//! A display driver instance without a framebuffer used as a return type from the builder. Used as
+//! a source to coerce the driver into richer modes like
+//! [`GraphicsMode`](../graphics/index.html), or to stream bytes straight into display RAM where
+//! there is no memory to spare for a framebuffer:
+//!
+//! ```rust,ignore
+//! let i2c = /* I2C interface from your HAL of choice */;
+//! let mut display = Builder::new().connect_i2c(i2c).unwrap().0;
+//!
+//! display.init().unwrap();
+//! // A checkerboard pattern in the top left corner
+//! display.write_page(Page::Page0, 0, &[0x55, 0xAA, 0x55, 0xAA]).unwrap();
+//! ```
+//!
+//! Each byte written to display RAM is a column of 8 pixels, with the least significant bit at the
+//! top.
 
 use crate::{
-    interface::DisplayInterface, mode::displaymode::DisplayModeTrait, properties::DisplayProperties,
+    displayrotation::DisplayRotation,
+    displaysize::{DisplaySize128x64, DisplaySizeType},
+    interface::{DisplayInterface, ReadableDisplayInterface},
+    mode::displaymode::DisplayModeTrait,
+    properties::{AddressMode, DisplayProperties, Page, Status},
+    DriverError,
 };
 
 /// Raw display mode
-pub struct RawMode<DI>
+pub struct RawMode<DI, SIZE = DisplaySize128x64>
 where
-    DI: DisplayInterface,
+    SIZE: DisplaySizeType,
 {
-    properties: DisplayProperties<DI>,
+    properties: DisplayProperties<DI, SIZE>,
 }
 
-impl<DI> DisplayModeTrait<DI> for RawMode<DI>
+impl<DI, SIZE> DisplayModeTrait<DI, SIZE> for RawMode<DI, SIZE>
 where
-    DI: DisplayInterface,
+    SIZE: DisplaySizeType,
 {
     /// Create new RawMode instance
-    fn new(properties: DisplayProperties<DI>) -> Self {
+    fn new(properties: DisplayProperties<DI, SIZE>) -> Self {
         RawMode { properties }
     }
 
     /// Release all resources used by RawMode
-    fn release(self) -> DisplayProperties<DI> {
+    fn release(self) -> DisplayProperties<DI, SIZE> {
         self.properties
     }
+
+    fn properties(&self) -> &DisplayProperties<DI, SIZE> {
+        &self.properties
+    }
+
+    fn properties_mut(&mut self) -> &mut DisplayProperties<DI, SIZE> {
+        &mut self.properties
+    }
 }
 
-impl<DI: DisplayInterface> RawMode<DI> {
+impl<DI: DisplayInterface, SIZE: DisplaySizeType> RawMode<DI, SIZE> {
     /// Create a new raw display mode
-    pub fn new(properties: DisplayProperties<DI>) -> Self {
+    pub fn new(properties: DisplayProperties<DI, SIZE>) -> Self {
         RawMode { properties }
     }
+
+    /// Display is set up in column mode, i.e. a byte walks down a column of 8 pixels from
+    /// column 0 on the left, to column _n_ on the right
+    pub fn init(&mut self) -> Result<(), DI::Error> {
+        self.properties.init_column_mode()
+    }
+
+    /// Set the area of display RAM written by subsequent `draw` calls. Coordinates are in display
+    /// RAM columns and rows, so any column offset of the panel must be included.
+    pub fn set_draw_area(&mut self, start: (u8, u8), end: (u8, u8)) -> Result<(), DI::Error> {
+        self.properties.set_draw_area(start, end)
+    }
+
+    /// Send data to the current position in the draw area and advance the position
+    pub fn draw(&mut self, buffer: &[u8]) -> Result<(), DI::Error> {
+        self.properties.draw(buffer)
+    }
+
+    /// Write bytes to a page of display RAM, starting at the given display column. Bytes which
+    /// don't fit into the remaining width of the display are ignored, a page or column outside of
+    /// the display fails with `OutOfBounds`.
+    pub fn write_page(&mut self, page: Page, column: u8, data: &[u8]) -> Result<(), DI::Error> {
+        let (display_width, display_height) = SIZE::SIZE.dimensions();
+        let column_offset = SIZE::SIZE.column_offset();
+        let row = page as u8 * 8;
+
+        if column >= display_width || row >= display_height {
+            return Err(DriverError::OutOfBounds.into());
+        }
+
+        if self.properties.get_address_mode() != AddressMode::Page {
+            self.properties.set_address_mode(AddressMode::Page)?;
+        }
+
+        self.properties.set_draw_area(
+            (column_offset + column, row),
+            (column_offset + display_width, row + 8),
+        )?;
+
+        let len = data.len().min((display_width - column) as usize);
+        self.properties.draw(&data[..len])
+    }
+
+    /// Get display dimensions, taking into account the current rotation of the display
+    pub fn get_dimensions(&self) -> (u8, u8) {
+        self.properties.get_dimensions()
+    }
+
+    /// Set the display rotation
+    pub fn set_rotation(&mut self, rot: DisplayRotation) -> Result<(), DI::Error> {
+        self.properties.set_rotation(rot)
+    }
+
+    /// Set the display contrast
+    pub fn set_contrast(&mut self, contrast: u8) -> Result<(), DI::Error> {
+        self.properties.set_contrast(contrast)
+    }
+
+    /// Turn the display on or off. Display RAM is retained while the display is off.
+    pub fn display_on(&mut self, on: bool) -> Result<(), DI::Error> {
+        self.properties.display_on(on)
+    }
+}
+
+impl<DI: ReadableDisplayInterface, SIZE: DisplaySizeType> RawMode<DI, SIZE> {
+    /// Read the status byte
+    pub fn read_status(&mut self) -> Result<Status, DI::Error> {
+        self.properties.read_status()
+    }
+
+    /// Read bytes from a page of display RAM, starting at the given display column. Returns the
+    /// number of bytes read, which stops at the right edge of the display. A column outside of
+    /// the display fails with `OutOfBounds`.
+    pub fn read_page(
+        &mut self,
+        page: Page,
+        column: u8,
+        buf: &mut [u8],
+    ) -> Result<usize, DI::Error> {
+        self.properties.read_page(page, column, buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RawMode;
+    use crate::{
+        displayrotation::DisplayRotation,
+        displaysize::DisplaySize128x64,
+        interface::mock::MockInterface,
+        properties::{DisplayProperties, Page},
+        Error,
+    };
+
+    #[test]
+    fn write_page_addresses_and_clips() {
+        let mut raw = RawMode::new(
+            DisplayProperties::new(
+                MockInterface::default(),
+                DisplaySize128x64,
+                DisplayRotation::Rotate0,
+            )
+            .initialised(),
+        );
+
+        raw.write_page(Page::Page3, 124, &[1, 2, 3, 4, 5, 6])
+            .unwrap();
+
+        let props = raw.properties;
+        // Page 3, column 124
+        assert_eq!(props.iface().commands()[..3], [[0xB3], [0x0C], [0x17]]);
+        assert_eq!(props.iface().data(), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn write_page_rejects_positions_outside_display() {
+        let mut raw = RawMode::new(
+            DisplayProperties::new(
+                MockInterface::default(),
+                DisplaySize128x64,
+                DisplayRotation::Rotate0,
+            )
+            .initialised(),
+        );
+
+        assert_eq!(
+            raw.write_page(Page::Page8, 0, &[1]),
+            Err(Error::OutOfBounds)
+        );
+        assert_eq!(
+            raw.write_page(Page::Page0, 128, &[1]),
+            Err(Error::OutOfBounds)
+        );
+        assert_eq!(raw.properties.iface().transfers, []);
+    }
 }
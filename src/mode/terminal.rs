@@ -0,0 +1,375 @@
+//! Unbuffered text terminal mode
+//!
+//! Renders characters from a built-in 8x8 font straight into display RAM, so no framebuffer is
+//! needed and embedded-graphics is not required. Implements [`core::fmt::Write`], which makes it a
+//! good fit for printing logs with `write!`:
+//!
+//! ```rust,ignore
+//! use core::fmt::Write;
+//!
+//! let i2c = /* I2C interface from your HAL of choice */;
+//! let mut display: TerminalMode<_> = Builder::new().connect_i2c(i2c).unwrap().into();
+//!
+//! display.init().unwrap();
+//! writeln!(display, "Booting v{}", 3).unwrap();
+//! ```
+//!
+//! Text wraps to the next line at the right edge of the display. When the bottom line is full,
+//! the display scrolls up by one line using the display start line, so no text has to be redrawn.
+//! The SH1107 can only scroll along its rows, so when the display is rotated by 90 or 270
+//! degrees the cursor wraps around to the top line instead, blanking each line as it enters it.
+
+use core::fmt;
+
+use crate::{
+    displayrotation::DisplayRotation,
+    displaysize::{DisplaySize128x64, DisplaySizeType},
+    interface::DisplayInterface,
+    mode::{displaymode::DisplayModeTrait, font8x8},
+    properties::{AddressMode, DisplayProperties},
+};
+
+/// Number of pages in SH1107 display RAM
+const RAM_PAGES: u8 = 16;
+
+/// Terminal mode handler
+pub struct TerminalMode<DI, SIZE = DisplaySize128x64>
+where
+    DI: DisplayInterface,
+    SIZE: DisplaySizeType,
+{
+    properties: DisplayProperties<DI, SIZE>,
+    column: u8,
+    row: u8,
+    top_page: u8,
+    /// The rotated terminal wrapped around to the top, so lines still show old text
+    wrapped: bool,
+}
+
+impl<DI, SIZE> DisplayModeTrait<DI, SIZE> for TerminalMode<DI, SIZE>
+where
+    DI: DisplayInterface,
+    SIZE: DisplaySizeType,
+{
+    /// Create new TerminalMode instance. Fails to compile for display sizes which don't fit a
+    /// single 8x8 character.
+    fn new(properties: DisplayProperties<DI, SIZE>) -> Self {
+        let () = Self::FITS_CHARACTER;
+
+        TerminalMode {
+            properties,
+            column: 0,
+            row: 0,
+            top_page: 0,
+            wrapped: false,
+        }
+    }
+
+    /// Release all resources used by TerminalMode
+    fn release(self) -> DisplayProperties<DI, SIZE> {
+        self.properties
+    }
+
+    fn properties(&self) -> &DisplayProperties<DI, SIZE> {
+        &self.properties
+    }
+
+    fn properties_mut(&mut self) -> &mut DisplayProperties<DI, SIZE> {
+        &mut self.properties
+    }
+}
+
+impl<DI, SIZE> TerminalMode<DI, SIZE>
+where
+    DI: DisplayInterface,
+    SIZE: DisplaySizeType,
+{
+    /// Evaluated by `new`, so the cursor arithmetic can rely on at least one character column
+    /// and row in every rotation
+    const FITS_CHARACTER: () = {
+        let (width, height) = SIZE::SIZE.dimensions();
+        assert!(
+            width >= 8 && height >= 8,
+            "terminal mode needs a display of at least 8x8 pixels"
+        );
+    };
+
+    /// Initialise the display, clear it and move the cursor to the top left corner
+    pub fn init(&mut self) -> Result<(), DI::Error> {
+        self.properties.init_column_mode()?;
+        self.clear()
+    }
+
+    /// Clear the display and move the cursor to the top left corner
+    pub fn clear(&mut self) -> Result<(), DI::Error> {
+        self.top_page = 0;
+        self.properties.set_start_line(0)?;
+
+        let (display_width, display_height) = SIZE::SIZE.dimensions();
+        for page in 0..display_height / 8 {
+            self.clear_page(page, display_width)?;
+        }
+
+        self.column = 0;
+        self.row = 0;
+        self.wrapped = false;
+
+        Ok(())
+    }
+
+    /// Get the number of character columns and rows, taking into account the current rotation of
+    /// the display
+    pub fn get_size(&self) -> (u8, u8) {
+        let (width, height) = self.properties.get_dimensions();
+
+        (width / 8, height / 8)
+    }
+
+    /// Get the cursor position as character column and row
+    pub fn get_position(&self) -> (u8, u8) {
+        let (columns, _) = self.get_size();
+
+        // A full line only wraps once the next character is printed
+        (self.column.min(columns - 1), self.row)
+    }
+
+    /// Move the cursor to the given character column and row. Positions outside of the terminal
+    /// are clamped to the last column or row.
+    pub fn set_position(&mut self, column: u8, row: u8) {
+        let (columns, rows) = self.get_size();
+
+        self.column = column.min(columns - 1);
+        self.row = row.min(rows - 1);
+    }
+
+    /// Print a character at the cursor position and advance the cursor. `'\n'` moves to the start
+    /// of the next line and `'\r'` to the start of the current line. Characters outside of
+    /// printable ASCII are shown as a filled block.
+    pub fn print_char(&mut self, c: char) -> Result<(), DI::Error> {
+        match c {
+            '\n' => self.new_line(),
+            '\r' => {
+                self.column = 0;
+                Ok(())
+            }
+            _ => {
+                let (columns, _) = self.get_size();
+                if self.column >= columns {
+                    self.new_line()?;
+                }
+
+                self.draw_glyph(c)?;
+                self.column += 1;
+
+                Ok(())
+            }
+        }
+    }
+
+    /// Print a string at the cursor position, see [`print_char`](#method.print_char)
+    pub fn print_str(&mut self, s: &str) -> Result<(), DI::Error> {
+        s.chars().try_for_each(|c| self.print_char(c))
+    }
+
+    /// Get display dimensions, taking into account the current rotation of the display
+    pub fn get_dimensions(&self) -> (u8, u8) {
+        self.properties.get_dimensions()
+    }
+
+    /// Set the display rotation. This clears the display as the character grid changes.
+    pub fn set_rotation(&mut self, rot: DisplayRotation) -> Result<(), DI::Error> {
+        self.properties.set_rotation(rot)?;
+        self.clear()
+    }
+
+    /// Set the display contrast
+    pub fn set_contrast(&mut self, contrast: u8) -> Result<(), DI::Error> {
+        self.properties.set_contrast(contrast)
+    }
+
+    fn rotated(&self) -> bool {
+        match self.properties.get_rotation() {
+            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => false,
+            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => true,
+        }
+    }
+
+    fn new_line(&mut self) -> Result<(), DI::Error> {
+        let (_, rows) = self.get_size();
+        let (display_width, _) = SIZE::SIZE.dimensions();
+
+        self.column = 0;
+
+        if self.row + 1 < rows {
+            self.row += 1;
+        } else if self.rotated() {
+            // Lines are columns of display RAM here, which can't be scrolled
+            self.row = 0;
+            self.wrapped = true;
+        } else {
+            // Blank the line which is about to appear at the bottom, then move the display start
+            // line down by one text line
+            let bottom_page = (self.top_page + rows) % RAM_PAGES;
+            self.clear_page(bottom_page, display_width)?;
+
+            self.top_page = (self.top_page + 1) % RAM_PAGES;
+            return self.properties.set_start_line(self.top_page * 8);
+        }
+
+        // After wrapping around, blank each line as the cursor enters it
+        if self.wrapped {
+            let (columns, _) = self.get_size();
+            for column in 0..columns {
+                self.draw_cell(column, self.row, [0; 8])?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn draw_glyph(&mut self, c: char) -> Result<(), DI::Error> {
+        let (column, row) = (self.column, self.row);
+
+        // Rotation by 90 or 270 degrees swaps X and Y in display RAM, which turns glyph rows into
+        // display RAM columns
+        let glyph = if self.rotated() {
+            font8x8::glyph(c)
+        } else {
+            font8x8::glyph_columns(c)
+        };
+
+        self.draw_cell(column, row, glyph)
+    }
+
+    /// Write 8 columns of pixels to the given character cell
+    fn draw_cell(&mut self, column: u8, row: u8, data: [u8; 8]) -> Result<(), DI::Error> {
+        let (ram_column, page) = if self.rotated() {
+            (row * 8, column)
+        } else {
+            (column * 8, (self.top_page + row) % RAM_PAGES)
+        };
+        let ram_column = ram_column + SIZE::SIZE.column_offset();
+
+        self.prepare_address_mode()?;
+        self.properties
+            .set_draw_area((ram_column, page * 8), (ram_column + 8, page * 8 + 8))?;
+        self.properties.draw(&data)
+    }
+
+    fn clear_page(&mut self, page: u8, width: u8) -> Result<(), DI::Error> {
+        let column_offset = SIZE::SIZE.column_offset();
+
+        self.prepare_address_mode()?;
+        self.properties.set_draw_area(
+            (column_offset, page * 8),
+            (column_offset + width, page * 8 + 8),
+        )?;
+
+        for _ in 0..width / 8 {
+            self.properties.draw(&[0; 8])?;
+        }
+
+        self.properties.draw(&[0; 8][..width as usize % 8])
+    }
+
+    fn prepare_address_mode(&mut self) -> Result<(), DI::Error> {
+        if self.properties.get_address_mode() != AddressMode::Page {
+            self.properties.set_address_mode(AddressMode::Page)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<DI, SIZE> fmt::Write for TerminalMode<DI, SIZE>
+where
+    DI: DisplayInterface,
+    SIZE: DisplaySizeType,
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.print_str(s).map_err(|_| fmt::Error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::fmt::Write;
+
+    use super::TerminalMode;
+    use crate::{
+        displayrotation::DisplayRotation, displaysize::DisplaySize128x32,
+        interface::mock::MockInterface, mode::displaymode::DisplayModeTrait,
+        properties::DisplayProperties,
+    };
+
+    fn terminal(rotation: DisplayRotation) -> TerminalMode<MockInterface, DisplaySize128x32> {
+        let mut term = TerminalMode::new(DisplayProperties::new(
+            MockInterface::default(),
+            DisplaySize128x32,
+            rotation,
+        ));
+        term.init().unwrap();
+        term.properties.iface_mut().clear();
+        term
+    }
+
+    #[test]
+    fn renders_glyph_columns() {
+        let mut term = terminal(DisplayRotation::Rotate0);
+
+        term.print_char('|').unwrap();
+
+        // '|' is a vertical bar in columns 3 and 4 with a gap in row 3
+        assert_eq!(
+            term.properties.iface().data(),
+            [0x00, 0x00, 0x00, 0x77, 0x77, 0x00, 0x00, 0x00]
+        );
+        assert_eq!(term.get_position(), (1, 0));
+    }
+
+    #[test]
+    fn wraps_and_scrolls() {
+        let mut term = terminal(DisplayRotation::Rotate0);
+        assert_eq!(term.get_size(), (16, 4));
+
+        for _ in 0..4 {
+            write!(term, "0123456789abcdef").unwrap();
+        }
+        // The last line is full but the display only scrolls once more text arrives
+        assert_eq!(term.get_position(), (15, 3));
+        assert!(!term
+            .properties
+            .iface()
+            .commands()
+            .iter()
+            .any(|c| c[0] == 0xDC));
+
+        term.print_char('!').unwrap();
+        assert_eq!(term.get_position(), (1, 3));
+        assert!(term
+            .properties
+            .iface()
+            .commands()
+            .iter()
+            .any(|c| c[..] == [0xDC, 8]));
+    }
+
+    #[test]
+    fn rotated_terminal_wraps_to_top() {
+        let mut term = terminal(DisplayRotation::Rotate90);
+        assert_eq!(term.get_size(), (4, 16));
+
+        term.set_position(2, 15);
+        writeln!(term, "x").unwrap();
+        assert_eq!(term.get_position(), (0, 0));
+
+        // Every line entered after wrapping is blanked, not just the first one
+        for row in 1..3 {
+            term.properties.iface_mut().clear();
+            writeln!(term).unwrap();
+
+            assert_eq!(term.get_position(), (0, row));
+            assert_eq!(term.properties.iface().data(), [0; 4 * 8]);
+        }
+    }
+}
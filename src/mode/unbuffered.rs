@@ -0,0 +1,233 @@
+//! Unbuffered graphics mode for use with the [embedded_graphics] crate
+//!
+//! Updates single pixels straight in display RAM using the SH1107's read-modify-write mode, so no
+//! framebuffer is needed at all. This requires an interface which can read from the display, i.e.
+//! I2C or parallel:
+//!
+//! ```rust,ignore
+//! let i2c = /* I2C interface from your HAL of choice */;
+//! let mut display: UnbufferedGraphicsMode<_> = Builder::new().connect_i2c(i2c).unwrap().into();
+//!
+//! display.init().unwrap();
+//! Circle::new(Point::new(64, 32), 16)
+//!     .into_styled(PrimitiveStyle::with_stroke(BinaryColor::On, 1))
+//!     .draw(&mut display)
+//!     .unwrap();
+//! ```
+//!
+//! Every pixel costs a few bus transactions, so this mode is much slower than
+//! [`GraphicsMode`](../graphics/struct.GraphicsMode.html) and best suited to drawing small
+//! amounts of content on MCUs which can't spare the RAM for a framebuffer.
+
+use crate::{
+    displayrotation::DisplayRotation,
+    displaysize::{DisplaySize128x64, DisplaySizeType},
+    interface::ReadableDisplayInterface,
+    mode::displaymode::DisplayModeTrait,
+    properties::{DisplayProperties, Page},
+};
+
+/// Unbuffered graphics mode handler
+pub struct UnbufferedGraphicsMode<DI, SIZE = DisplaySize128x64>
+where
+    DI: ReadableDisplayInterface,
+    SIZE: DisplaySizeType,
+{
+    properties: DisplayProperties<DI, SIZE>,
+}
+
+impl<DI, SIZE> DisplayModeTrait<DI, SIZE> for UnbufferedGraphicsMode<DI, SIZE>
+where
+    DI: ReadableDisplayInterface,
+    SIZE: DisplaySizeType,
+{
+    /// Create new UnbufferedGraphicsMode instance
+    fn new(properties: DisplayProperties<DI, SIZE>) -> Self {
+        UnbufferedGraphicsMode { properties }
+    }
+
+    /// Release all resources used by UnbufferedGraphicsMode
+    fn release(self) -> DisplayProperties<DI, SIZE> {
+        self.properties
+    }
+
+    fn properties(&self) -> &DisplayProperties<DI, SIZE> {
+        &self.properties
+    }
+
+    fn properties_mut(&mut self) -> &mut DisplayProperties<DI, SIZE> {
+        &mut self.properties
+    }
+}
+
+impl<DI, SIZE> UnbufferedGraphicsMode<DI, SIZE>
+where
+    DI: ReadableDisplayInterface,
+    SIZE: DisplaySizeType,
+{
+    /// Initialise the display and clear it
+    pub fn init(&mut self) -> Result<(), DI::Error> {
+        self.properties.init_column_mode()?;
+        self.clear()
+    }
+
+    /// Turn all pixels of the display off
+    pub fn clear(&mut self) -> Result<(), DI::Error> {
+        self.fill(0x00)
+    }
+
+    /// Write the same byte to every column of every page
+    fn fill(&mut self, byte: u8) -> Result<(), DI::Error> {
+        let (display_width, display_height) = SIZE::SIZE.dimensions();
+        let column_offset = SIZE::SIZE.column_offset();
+        let bytes = [byte; 32];
+
+        self.properties.set_draw_area(
+            (column_offset, 0),
+            (column_offset + display_width, display_height),
+        )?;
+
+        let mut remaining = display_width as usize * display_height as usize / 8;
+        while remaining > 0 {
+            let len = remaining.min(bytes.len());
+            self.properties.draw(&bytes[..len])?;
+            remaining -= len;
+        }
+
+        Ok(())
+    }
+
+    /// Turn a pixel on or off. A non-zero `value` is treated as on, `0` as off. If the X and Y
+    /// coordinates are out of the bounds of the display, this method call is a noop.
+    pub fn set_pixel(&mut self, x: u32, y: u32, value: u8) -> Result<(), DI::Error> {
+        let (display_width, display_height) = SIZE::SIZE.dimensions();
+
+        // Rotation by 90 or 270 degrees swaps the roles of X and Y in display RAM
+        let (column, row) = match self.properties.get_rotation() {
+            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => (x, y),
+            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => (y, x),
+        };
+
+        if column >= display_width as u32 || row >= display_height as u32 {
+            return Ok(());
+        }
+
+        let bit = 1 << (row % 8);
+        self.properties
+            .read_modify_write(Page::from_row(row as u8), column as u8, |byte| {
+                if value == 0 {
+                    byte & !bit
+                } else {
+                    byte | bit
+                }
+            })
+    }
+
+    /// Get display dimensions, taking into account the current rotation of the display
+    pub fn get_dimensions(&self) -> (u8, u8) {
+        self.properties.get_dimensions()
+    }
+
+    /// Set the display rotation. Display RAM isn't redrawn, so the display should be cleared
+    /// afterwards.
+    pub fn set_rotation(&mut self, rot: DisplayRotation) -> Result<(), DI::Error> {
+        self.properties.set_rotation(rot)
+    }
+
+    /// Set the display contrast
+    pub fn set_contrast(&mut self, contrast: u8) -> Result<(), DI::Error> {
+        self.properties.set_contrast(contrast)
+    }
+}
+
+#[cfg(feature = "graphics")]
+use embedded_graphics::{
+    drawable,
+    geometry::Size,
+    pixelcolor::{
+        raw::{RawData, RawU1},
+        BinaryColor,
+    },
+    DrawTarget,
+};
+
+#[cfg(feature = "graphics")]
+impl<DI, SIZE> DrawTarget<BinaryColor> for UnbufferedGraphicsMode<DI, SIZE>
+where
+    DI: ReadableDisplayInterface,
+    SIZE: DisplaySizeType,
+{
+    type Error = DI::Error;
+
+    fn draw_pixel(&mut self, pixel: drawable::Pixel<BinaryColor>) -> Result<(), Self::Error> {
+        let drawable::Pixel(pos, color) = pixel;
+
+        // Guard against negative values. All positive i32 values from `pos` can be represented in
+        // the `u32`s that `set_pixel()` accepts...
+        if pos.x < 0 || pos.y < 0 {
+            return Ok(());
+        }
+
+        // ... which makes the `as` coercions here safe.
+        self.set_pixel(pos.x as u32, pos.y as u32, RawU1::from(color).into_inner())
+    }
+
+    fn size(&self) -> Size {
+        let (w, h) = self.get_dimensions();
+
+        Size::new(w as u32, h as u32)
+    }
+
+    fn clear(&mut self, color: BinaryColor) -> Result<(), Self::Error> {
+        match color {
+            BinaryColor::Off => self.fill(0x00),
+            BinaryColor::On => self.fill(0xFF),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UnbufferedGraphicsMode;
+    use crate::{
+        displayrotation::DisplayRotation,
+        displaysize::DisplaySize128x64,
+        interface::mock::{MockInterface, Transfer},
+        mode::displaymode::DisplayModeTrait,
+        properties::DisplayProperties,
+    };
+
+    #[test]
+    fn set_pixel_uses_read_modify_write() {
+        let mut disp: UnbufferedGraphicsMode<_> = UnbufferedGraphicsMode::new(
+            DisplayProperties::new(
+                MockInterface::default(),
+                DisplaySize128x64,
+                DisplayRotation::Rotate0,
+            )
+            .initialised(),
+        );
+
+        disp.set_pixel(5, 10, 1).unwrap();
+        // The mock reads 0x00, so turning a pixel off changes nothing and isn't written
+        disp.set_pixel(6, 10, 0).unwrap();
+
+        let props = disp.release();
+        assert_eq!(
+            props.iface().transfers[..9],
+            [
+                // Page 1, column 5
+                Transfer::Commands([0xB1].to_vec()),
+                Transfer::Commands([0x05].to_vec()),
+                Transfer::Commands([0x10].to_vec()),
+                Transfer::Commands([0xE0].to_vec()),
+                Transfer::Read(1),
+                Transfer::Read(1),
+                Transfer::Data([0x04].to_vec()),
+                Transfer::Commands([0xEE].to_vec()),
+                Transfer::Commands([0xB1].to_vec()),
+            ]
+        );
+        assert!(!props.iface().transfers[9..].contains(&Transfer::Data([0x00].to_vec())));
+    }
+}
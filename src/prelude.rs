@@ -1,8 +1,23 @@
 //! Crate prelude
 
 pub use super::{
+    config::{ConfigError, PanelConfig, VcomhLevel},
     displayrotation::DisplayRotation,
-    displaysize::DisplaySize,
-    interface::{I2cInterface, SpiInterface},
-    mode::GraphicsMode,
+    displaysize::{
+        DisplaySize, DisplaySize128x128, DisplaySize128x32, DisplaySize128x64,
+        DisplaySize128x64NoOffset, DisplaySize64x128, DisplaySizeType,
+    },
+    interface::{
+        parallel::{Generic8BitBus, ParallelProtocol},
+        I2cInterface, ParallelInterface, Spi3WireInterface, SpiInterface,
+    },
+    mode::{
+        displaymode::DisplayModeTrait, DoubleBufferedGraphicsMode, GraphicsMode, TerminalMode,
+        UnbufferedGraphicsMode,
+    },
+    properties::{AddressMode, InvalidPage, Page, PowerState, Status},
+    DriverError, Error,
 };
+
+#[cfg(feature = "display-interface")]
+pub use super::interface::WriteOnlyInterface;
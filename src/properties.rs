@@ -1,137 +1,140 @@
 //! Container to store and set display properties
 
+use core::marker::PhantomData;
+
+pub use crate::command::{AddressMode, InvalidPage, Page};
+
+use crate::hal::DelayMs;
+#[cfg(feature = "async")]
+use crate::interface::AsyncDisplayInterface;
 use crate::{
-    command::{Command, VcomhLevel},
+    command::Command,
+    config::PanelConfig,
     displayrotation::DisplayRotation,
-    displaysize::DisplaySize,
-    interface::DisplayInterface,
+    displaysize::{DisplaySize, DisplaySizeType},
+    hal::OutputPin,
+    interface::{DisplayInterface, DmaDisplayInterface, ReadableDisplayInterface},
+    DriverError, Error,
 };
 
+/// Time for an external VPP supply to settle after it was enabled
+const VPP_SETTLE_MS: u8 = 10;
+
+/// Time for the panel to discharge after VPP was disabled (tOFF in the datasheet)
+const POWER_OFF_MS: u8 = 100;
+
+/// Power state of the display as last set by the driver
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PowerState {
+    /// Not initialised yet, or powered down by `power_off`
+    Off,
+    /// Initialised with the DC-DC converter running
+    On,
+    /// Display and DC-DC converter turned off by `sleep`. Display RAM is retained.
+    Sleep,
+}
+
+/// Contents of the SH1107 status byte, as returned by
+/// [`DisplayProperties::read_status`](struct.DisplayProperties.html#method.read_status)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Status(u8);
+
+impl Status {
+    /// The controller is busy with an internal operation
+    pub fn is_busy(self) -> bool {
+        self.0 & 0x80 != 0
+    }
+
+    /// The display is on. A panel which was reset by a brown-out reports that it is off.
+    pub fn is_display_on(self) -> bool {
+        self.0 & 0x40 == 0
+    }
+
+    /// The raw status byte
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+}
+
 /// Display properties struct
-pub struct DisplayProperties<DI> {
+pub struct DisplayProperties<DI, SIZE> {
     iface: DI,
-    display_size: DisplaySize,
+    display_size: PhantomData<SIZE>,
     display_rotation: DisplayRotation,
+    address_mode: AddressMode,
     draw_area_start: (u8, u8),
     draw_area_end: (u8, u8),
     draw_column: u8,
     draw_row: u8,
+    power_state: PowerState,
+    config: PanelConfig,
 }
 
-impl<DI> DisplayProperties<DI>
+impl<DI, SIZE> DisplayProperties<DI, SIZE>
 where
-    DI: DisplayInterface,
+    SIZE: DisplaySizeType,
 {
     /// Create new DisplayProperties instance
     pub fn new(
         iface: DI,
-        display_size: DisplaySize,
+        _display_size: SIZE,
         display_rotation: DisplayRotation,
-    ) -> DisplayProperties<DI> {
+    ) -> DisplayProperties<DI, SIZE> {
         DisplayProperties {
             iface,
-            display_size,
+            display_size: PhantomData,
             display_rotation,
+            address_mode: AddressMode::Page,
             draw_area_start: (0, 0),
             draw_area_end: (0, 0),
             draw_column: 0,
             draw_row: 0,
+            power_state: PowerState::Off,
+            config: PanelConfig::for_size(SIZE::SIZE),
         }
     }
 
-    /// Initialise the display in column mode (i.e. a byte walks down a column of 8 pixels) with
-    /// column 0 on the left and column _(display_width - 1)_ on the right.
-    pub fn init_column_mode(&mut self) -> Result<(), DI::Error> {
-        self.iface.init()?;
-        // TODO: Break up into nice bits so display modes can pick whathever they need
-        let (_, display_height) = self.display_size.dimensions();
-        let display_rotation = self.display_rotation;
-
-        Command::DisplayOn(false).send(&mut self.iface)?;
-        Command::DisplayClockDiv(0x8, 0x0).send(&mut self.iface)?;
-        Command::Multiplex(display_height - 1).send(&mut self.iface)?;
-
-        // TODO: combine with match below
-        match self.display_size {
-            DisplaySize::Display64x128 => Command::DisplayOffset(0x60).send(&mut self.iface),
-            DisplaySize::Display128x32
-            | DisplaySize::Display128x64
-            | DisplaySize::Display128x64NoOffset
-            | DisplaySize::Display132x64 => Command::DisplayOffset(0).send(&mut self.iface),
-        }?;
-
-        Command::StartLine(0).send(&mut self.iface)?;
-        // TODO: Ability to turn charge pump on/off
-        // Display must be off when performing this command
-        Command::ChargePump(true).send(&mut self.iface)?;
-
-        self.set_rotation(display_rotation)?;
-
-        match self.display_size {
-            DisplaySize::Display128x32 => Command::ComPinConfig(false).send(&mut self.iface),
-            DisplaySize::Display64x128
-            | DisplaySize::Display128x64
-            | DisplaySize::Display128x64NoOffset
-            | DisplaySize::Display132x64 => Command::ComPinConfig(true).send(&mut self.iface),
-        }?;
-
-        Command::Contrast(0x80).send(&mut self.iface)?;
-        Command::PreChargePeriod(0x1, 0xF).send(&mut self.iface)?;
-        Command::VcomhDeselect(VcomhLevel::Auto).send(&mut self.iface)?;
-        Command::AllOn(false).send(&mut self.iface)?;
-        Command::Invert(false).send(&mut self.iface)?;
-        Command::DisplayOn(true).send(&mut self.iface)?;
-
-        Ok(())
+    /// Use the given panel settings instead of the defaults for the display size. They take
+    /// effect on the next `init`, which checks them against the datasheet ranges first.
+    pub fn with_panel_config(self, config: PanelConfig) -> Self {
+        Self { config, ..self }
     }
 
-    /// Set the position in the framebuffer of the display where any sent data should be
-    /// drawn. This method can be used for changing the affected area on the screen as well
-    /// as (re-)setting the start point of the next `draw` call.
-    pub fn set_draw_area(&mut self, start: (u8, u8), end: (u8, u8)) -> Result<(), DI::Error> {
-        self.draw_area_start = start;
-        self.draw_area_end = end;
-        self.draw_column = start.0;
-        self.draw_row = start.1;
-
-        self.send_draw_address()
+    /// Get the panel settings sent by `init`
+    pub fn get_panel_config(&self) -> PanelConfig {
+        self.config
     }
 
-    /// Send the data to the display for drawing at the current position in the framebuffer
-    /// and advance the position accordingly. Cf. `set_draw_area` to modify the affected area by
-    /// this method.
-    pub fn draw(&mut self, mut buffer: &[u8]) -> Result<(), DI::Error> {
-        while !buffer.is_empty() {
-            let count = self.draw_area_end.0 - self.draw_column;
-            self.iface.send_data(&buffer[..count as usize])?;
-            self.draw_column += count;
-
-            if self.draw_column >= self.draw_area_end.0 {
-                self.draw_column = self.draw_area_start.0;
-
-                self.draw_row += 8;
-                if self.draw_row >= self.draw_area_end.1 {
-                    self.draw_row = self.draw_area_start.1;
-                }
+    /// Consume the display properties and return the interface
+    pub fn release(self) -> DI {
+        self.iface
+    }
 
-                self.send_draw_address()?;
-            }
+    /// Get the power state as last set by the driver
+    pub fn get_power_state(&self) -> PowerState {
+        self.power_state
+    }
 
-            buffer = &buffer[count as usize..];
-        }
+    /// Get the current memory addressing mode
+    pub fn get_address_mode(&self) -> AddressMode {
+        self.address_mode
+    }
 
-        Ok(())
+    /// Access the interface to inspect what was sent
+    #[cfg(test)]
+    pub(crate) fn iface(&self) -> &DI {
+        &self.iface
     }
 
-    fn send_draw_address(&mut self) -> Result<(), DI::Error> {
-        Command::PageAddress(self.draw_row.into()).send(&mut self.iface)?;
-        Command::ColumnAddressLow(0xF & self.draw_column).send(&mut self.iface)?;
-        Command::ColumnAddressHigh(0xF & (self.draw_column >> 4)).send(&mut self.iface)
+    /// Access the interface to reset what was recorded
+    #[cfg(test)]
+    pub(crate) fn iface_mut(&mut self) -> &mut DI {
+        &mut self.iface
     }
 
     /// Get the configured display size
     pub fn get_size(&self) -> DisplaySize {
-        self.display_size
+        SIZE::SIZE
     }
 
     /// Get display dimensions, taking into account the current rotation of the display
@@ -148,7 +151,7 @@ where
     /// #
     /// let disp = DisplayProperties::new(
     ///     interface,
-    ///     DisplaySize::Display128x64,
+    ///     DisplaySize128x64,
     ///     DisplayRotation::Rotate0,
     /// );
     /// assert_eq!(disp.get_dimensions(), (128, 64));
@@ -156,13 +159,13 @@ where
     /// # let interface = FakeInterface {};
     /// let rotated_disp = DisplayProperties::new(
     ///     interface,
-    ///     DisplaySize::Display128x64,
+    ///     DisplaySize128x64,
     ///     DisplayRotation::Rotate90,
     /// );
     /// assert_eq!(rotated_disp.get_dimensions(), (64, 128));
     /// ```
     pub fn get_dimensions(&self) -> (u8, u8) {
-        let (w, h) = self.display_size.dimensions();
+        let (w, h) = SIZE::SIZE.dimensions();
 
         match self.display_rotation {
             DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => (w, h),
@@ -175,32 +178,870 @@ where
         self.display_rotation
     }
 
-    /// Set the display rotation
-    pub fn set_rotation(&mut self, display_rotation: DisplayRotation) -> Result<(), DI::Error> {
-        self.display_rotation = display_rotation;
+    /// Commands sent by `init_column_mode`, after initialising the interface. The panel config
+    /// must have been validated.
+    fn init_commands(&self) -> impl Iterator<Item = Command> {
+        let (_, display_height) = SIZE::SIZE.dimensions();
+        let [segment_remap, com_dir] = Self::rotation_commands(self.display_rotation);
+        let config = &self.config;
 
+        let commands = [
+            Some(Command::DisplayOn(false)),
+            Some(Command::DisplayClockDiv(
+                config.oscillator_frequency,
+                config.clock_divide - 1,
+            )),
+            Some(Command::Multiplex(display_height - 1)),
+            Some(Command::DisplayOffset(config.display_offset)),
+            Some(Command::StartLine(0)),
+            Some(Command::AddressMode(AddressMode::Page)),
+            // Display must be off when performing this command
+            Some(Command::DcDc(config.dc_dc, config.dc_dc_frequency)),
+            Some(segment_remap),
+            Some(com_dir),
+            config.alternative_com_pins.map(Command::ComPinConfig),
+            Some(Command::Contrast(config.contrast)),
+            Some(Command::PreChargePeriod(
+                config.precharge_period,
+                config.discharge_period,
+            )),
+            Some(Command::VcomhDeselect(config.vcomh_level)),
+            Some(Command::AllOn(false)),
+            Some(Command::Invert(false)),
+            Some(Command::DisplayOn(true)),
+        ];
+
+        IntoIterator::into_iter(commands).flatten()
+    }
+
+    /// Segment remap and COM scan direction commands for a display rotation
+    fn rotation_commands(display_rotation: DisplayRotation) -> [Command; 2] {
         match display_rotation {
-            DisplayRotation::Rotate0 => {
-                Command::SegmentRemap(true).send(&mut self.iface)?;
-                Command::ReverseComDir(true).send(&mut self.iface)
-            }
+            DisplayRotation::Rotate0 => [Command::SegmentRemap(true), Command::ReverseComDir(true)],
             DisplayRotation::Rotate90 => {
-                Command::SegmentRemap(false).send(&mut self.iface)?;
-                Command::ReverseComDir(true).send(&mut self.iface)
+                [Command::SegmentRemap(false), Command::ReverseComDir(true)]
             }
             DisplayRotation::Rotate180 => {
-                Command::SegmentRemap(false).send(&mut self.iface)?;
-                Command::ReverseComDir(false).send(&mut self.iface)
+                [Command::SegmentRemap(false), Command::ReverseComDir(false)]
             }
             DisplayRotation::Rotate270 => {
-                Command::SegmentRemap(true).send(&mut self.iface)?;
-                Command::ReverseComDir(false).send(&mut self.iface)
+                [Command::SegmentRemap(true), Command::ReverseComDir(false)]
             }
         }
     }
 
+    /// Reset the draw position to the start of a new draw area
+    fn start_draw_area(&mut self, start: (u8, u8), end: (u8, u8)) {
+        self.draw_area_start = start;
+        self.draw_area_end = end;
+        self.draw_column = start.0;
+        self.draw_row = start.1;
+    }
+
+    /// Number of bytes of a `len` byte buffer which can be sent before the address has to be
+    /// advanced
+    fn draw_chunk_len(&self, len: usize) -> usize {
+        let remaining = match self.address_mode {
+            AddressMode::Page => self.draw_area_end.0 - self.draw_column,
+            AddressMode::Vertical => (self.draw_area_end.1 - self.draw_row).div_ceil(8),
+        };
+
+        len.min(remaining as usize)
+    }
+
+    /// Advance the draw position past `count` sent bytes. Returns whether the new position has to
+    /// be sent to the display.
+    fn advance_draw(&mut self, count: usize) -> bool {
+        match self.address_mode {
+            AddressMode::Page => {
+                self.draw_column += count as u8;
+
+                if self.draw_column < self.draw_area_end.0 {
+                    return false;
+                }
+
+                self.draw_column = self.draw_area_start.0;
+
+                self.draw_row += 8;
+                if self.draw_row >= self.draw_area_end.1 {
+                    self.draw_row = self.draw_area_start.1;
+                }
+
+                true
+            }
+            AddressMode::Vertical => {
+                self.draw_row += 8 * count as u8;
+
+                if self.draw_row < self.draw_area_end.1 {
+                    return false;
+                }
+
+                self.draw_row = self.draw_area_start.1;
+
+                self.draw_column += 1;
+                let wrapped = self.draw_column >= self.draw_area_end.0;
+                if wrapped {
+                    self.draw_column = self.draw_area_start.0;
+                }
+
+                // The page address wraps from the last to the first page of display RAM and moves
+                // on to the next column by itself, so a draw area covering all pages needs no
+                // further addressing until it wraps around
+                wrapped || self.draw_area_start.1 != 0 || self.draw_area_end.1 < 128
+            }
+        }
+    }
+
+    /// Commands moving the display's address pointer to the current draw position
+    fn draw_address_commands(&self) -> [Command; 3] {
+        Self::address_commands(self.draw_column, self.draw_row)
+    }
+
+    /// Fail with `NotInitialised` unless the display was initialised since it was last powered up
+    fn check_initialised<E: From<DriverError>>(&self) -> Result<(), E> {
+        if self.power_state == PowerState::Off {
+            return Err(DriverError::NotInitialised.into());
+        }
+
+        Ok(())
+    }
+
+    /// Commands moving the display's address pointer to a column and row of display RAM
+    fn address_commands(column: u8, row: u8) -> [Command; 3] {
+        [
+            Command::PageAddress(Page::from_row(row)),
+            Command::ColumnAddressLow(0xF & column),
+            Command::ColumnAddressHigh(0x7 & (column >> 4)),
+        ]
+    }
+}
+
+impl<DI, SIZE> DisplayProperties<DI, SIZE>
+where
+    DI: DisplayInterface,
+    SIZE: DisplaySizeType,
+{
+    /// Initialise the display in column mode (i.e. a byte walks down a column of 8 pixels) with
+    /// column 0 on the left and column _(display_width - 1)_ on the right. The display is left in
+    /// page addressing mode.
+    ///
+    /// Fails with `Config` without sending anything if a panel setting is outside of the range
+    /// given in the datasheet, see [`PanelConfig::validate`].
+    pub fn init_column_mode(&mut self) -> Result<(), DI::Error> {
+        self.config.validate().map_err(DriverError::Config)?;
+        self.iface.init()?;
+        self.address_mode = AddressMode::Page;
+
+        for command in self.init_commands() {
+            command.send(&mut self.iface)?;
+        }
+        self.power_state = PowerState::On;
+
+        Ok(())
+    }
+
+    /// Set the position in the framebuffer of the display where any sent data should be
+    /// drawn. This method can be used for changing the affected area on the screen as well
+    /// as (re-)setting the start point of the next `draw` call. Fails with `NotInitialised`
+    /// before `init`.
+    pub fn set_draw_area(&mut self, start: (u8, u8), end: (u8, u8)) -> Result<(), DI::Error> {
+        self.check_initialised()?;
+        self.start_draw_area(start, end);
+
+        self.send_draw_address()
+    }
+
+    /// Send the data to the display for drawing at the current position in the framebuffer
+    /// and advance the position accordingly. Cf. `set_draw_area` to modify the affected area by
+    /// this method. Fails with `NotInitialised` before `init`.
+    ///
+    /// In [`AddressMode::Page`] the data is laid out page by page, each page holding the columns
+    /// of the draw area from left to right. In [`AddressMode::Vertical`] it is laid out column by
+    /// column, each column holding the pages of the draw area from top to bottom.
+    pub fn draw(&mut self, mut buffer: &[u8]) -> Result<(), DI::Error> {
+        self.check_initialised()?;
+
+        while !buffer.is_empty() {
+            let count = self.draw_chunk_len(buffer.len());
+            self.iface.send_data(&buffer[..count])?;
+
+            if self.advance_draw(count) {
+                self.send_draw_address()?;
+            }
+
+            buffer = &buffer[count..];
+        }
+
+        Ok(())
+    }
+
+    /// Set the memory addressing mode used by subsequent `draw` calls. This also changes the
+    /// expected layout of the data passed to `draw`.
+    pub fn set_address_mode(&mut self, address_mode: AddressMode) -> Result<(), DI::Error> {
+        self.address_mode = address_mode;
+
+        Command::AddressMode(address_mode).send(&mut self.iface)
+    }
+
+    fn send_draw_address(&mut self) -> Result<(), DI::Error> {
+        for command in self.draw_address_commands() {
+            command.send(&mut self.iface)?;
+        }
+
+        Ok(())
+    }
+
+    /// Set the display rotation
+    pub fn set_rotation(&mut self, display_rotation: DisplayRotation) -> Result<(), DI::Error> {
+        self.display_rotation = display_rotation;
+
+        for command in Self::rotation_commands(display_rotation) {
+            command.send(&mut self.iface)?;
+        }
+
+        Ok(())
+    }
+
     /// Set the display contrast
     pub fn set_contrast(&mut self, contrast: u8) -> Result<(), DI::Error> {
         Command::Contrast(contrast).send(&mut self.iface)
     }
+
+    /// Turn the display on or off. Display RAM is retained while the display is off.
+    pub fn display_on(&mut self, on: bool) -> Result<(), DI::Error> {
+        Command::DisplayOn(on).send(&mut self.iface)
+    }
+
+    /// Set the row of display RAM shown at the top of the display, from 0-127. Display RAM wraps
+    /// around, so this scrolls the display contents vertically. Other rows fail with
+    /// `InvalidArgument`.
+    pub fn set_start_line(&mut self, line: u8) -> Result<(), DI::Error> {
+        if line > 0x7F {
+            return Err(DriverError::InvalidArgument.into());
+        }
+
+        Command::StartLine(line).send(&mut self.iface)
+    }
+
+    /// Turn the display off and then stop the DC-DC converter, which is the order required by the
+    /// datasheet. Display RAM and all settings are retained, so [`wake`](#method.wake) brings the
+    /// display back with its previous contents. Fails with `NotInitialised` before `init`.
+    pub fn sleep(&mut self) -> Result<(), DI::Error> {
+        self.check_initialised()?;
+
+        Command::DisplayOn(false).send(&mut self.iface)?;
+        Command::DcDc(false, self.config.dc_dc_frequency).send(&mut self.iface)?;
+        self.power_state = PowerState::Sleep;
+
+        Ok(())
+    }
+
+    /// Start the DC-DC converter, unless it is disabled in the panel config, and turn the display
+    /// back on after [`sleep`](#method.sleep). Fails with `NotInitialised` before `init`.
+    pub fn wake(&mut self) -> Result<(), DI::Error> {
+        self.check_initialised()?;
+
+        Command::DcDc(self.config.dc_dc, self.config.dc_dc_frequency).send(&mut self.iface)?;
+        Command::DisplayOn(true).send(&mut self.iface)?;
+        self.power_state = PowerState::On;
+
+        Ok(())
+    }
+
+    /// Run the power up sequence from the datasheet: pulse RST while VDD is stable, then enable
+    /// the external VPP supply and wait for it to settle. Call `init` on the display mode
+    /// afterwards to configure the panel and turn it on.
+    ///
+    /// Pass [`NoOutputPin`](../builder/struct.NoOutputPin.html) for pins which aren't connected,
+    /// e.g. for VPP when the internal DC-DC converter is used. Pin errors are reported through the
+    /// interface's [`Error`], so the pins must have the same error type as the interface's pins,
+    /// which is `Infallible` for interfaces without pins like I2C.
+    pub fn power_on<RST, VPP, DELAY, CommE, PinE>(
+        &mut self,
+        rst: &mut RST,
+        vpp: &mut VPP,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<CommE, PinE>>
+    where
+        DI: DisplayInterface<Error = Error<CommE, PinE>>,
+        RST: OutputPin<Error = PinE>,
+        VPP: OutputPin<Error = PinE>,
+        DELAY: DelayMs<u8>,
+    {
+        self.power_on_with(rst, vpp, |ms| delay.delay_ms(ms))
+    }
+
+    /// Run the power down sequence from the datasheet: turn the display off, disable the external
+    /// VPP supply, wait for the panel to discharge and hold the controller in reset. VDD can be
+    /// removed once this returns. Errors are reported like for [`power_on`](#method.power_on).
+    pub fn power_off<RST, VPP, DELAY, CommE, PinE>(
+        &mut self,
+        rst: &mut RST,
+        vpp: &mut VPP,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<CommE, PinE>>
+    where
+        DI: DisplayInterface<Error = Error<CommE, PinE>>,
+        RST: OutputPin<Error = PinE>,
+        VPP: OutputPin<Error = PinE>,
+        DELAY: DelayMs<u8>,
+    {
+        self.power_off_with(rst, vpp, |ms| delay.delay_ms(ms))
+    }
+
+    fn power_on_with<RST, VPP, CommE, PinE>(
+        &mut self,
+        rst: &mut RST,
+        vpp: &mut VPP,
+        mut delay_ms: impl FnMut(u8),
+    ) -> Result<(), Error<CommE, PinE>>
+    where
+        DI: DisplayInterface<Error = Error<CommE, PinE>>,
+        RST: OutputPin<Error = PinE>,
+        VPP: OutputPin<Error = PinE>,
+    {
+        // RST has to be low for at least 10us once VDD is stable
+        rst.set_low().map_err(Error::Pin)?;
+        delay_ms(1);
+        rst.set_high().map_err(Error::Pin)?;
+        delay_ms(1);
+
+        vpp.set_high().map_err(Error::Pin)?;
+        delay_ms(VPP_SETTLE_MS);
+
+        self.address_mode = AddressMode::Page;
+        self.power_state = PowerState::Off;
+
+        Ok(())
+    }
+
+    fn power_off_with<RST, VPP, CommE, PinE>(
+        &mut self,
+        rst: &mut RST,
+        vpp: &mut VPP,
+        mut delay_ms: impl FnMut(u8),
+    ) -> Result<(), Error<CommE, PinE>>
+    where
+        DI: DisplayInterface<Error = Error<CommE, PinE>>,
+        RST: OutputPin<Error = PinE>,
+        VPP: OutputPin<Error = PinE>,
+    {
+        self.display_on(false)?;
+
+        vpp.set_low().map_err(Error::Pin)?;
+        delay_ms(POWER_OFF_MS);
+        self.power_state = PowerState::Off;
+
+        rst.set_low().map_err(Error::Pin)
+    }
+}
+
+impl<DI, SIZE> DisplayProperties<DI, SIZE>
+where
+    DI: ReadableDisplayInterface,
+    SIZE: DisplaySizeType,
+{
+    /// Read the status byte. An I2C panel which isn't connected fails with a bus error instead.
+    pub fn read_status(&mut self) -> Result<Status, DI::Error> {
+        self.iface.read_status().map(Status)
+    }
+
+    /// Read bytes from a page of display RAM, starting at the given display column. Reading
+    /// stops at the right edge of the display, returning the number of bytes read. A page or
+    /// column outside of the display fails with `OutOfBounds`, and reading before `init` with
+    /// `NotInitialised`. The draw position used by `draw` is restored afterwards.
+    pub fn read_page(
+        &mut self,
+        page: Page,
+        column: u8,
+        buf: &mut [u8],
+    ) -> Result<usize, DI::Error> {
+        self.check_initialised()?;
+
+        let (display_width, display_height) = SIZE::SIZE.dimensions();
+        let column_offset = SIZE::SIZE.column_offset();
+
+        if column >= display_width || page as u8 * 8 >= display_height {
+            return Err(DriverError::OutOfBounds.into());
+        }
+
+        if self.address_mode != AddressMode::Page {
+            self.set_address_mode(AddressMode::Page)?;
+        }
+
+        for command in Self::address_commands(column_offset + column, page as u8 * 8) {
+            command.send(&mut self.iface)?;
+        }
+
+        // The first read after setting the address returns a dummy byte
+        let len = buf.len().min((display_width - column) as usize);
+        self.iface.read_data(&mut [0])?;
+        self.iface.read_data(&mut buf[..len])?;
+
+        self.send_draw_address()?;
+
+        Ok(len)
+    }
+
+    /// Update a single byte of display RAM in read-modify-write mode, passing its current value
+    /// to `f` and writing back the result. Nothing is written if the value is unchanged. The
+    /// display's address pointer is left on the modified byte, so `set_draw_area` has to be called
+    /// before the next `draw`. A page or column outside of the display fails with `OutOfBounds`,
+    /// and using it before `init` with `NotInitialised`.
+    pub fn read_modify_write<F>(&mut self, page: Page, column: u8, f: F) -> Result<(), DI::Error>
+    where
+        F: FnOnce(u8) -> u8,
+    {
+        self.check_initialised()?;
+
+        let (display_width, display_height) = SIZE::SIZE.dimensions();
+        if column >= display_width || page as u8 * 8 >= display_height {
+            return Err(DriverError::OutOfBounds.into());
+        }
+
+        if self.address_mode != AddressMode::Page {
+            self.set_address_mode(AddressMode::Page)?;
+        }
+
+        let column = SIZE::SIZE.column_offset() + column;
+        for command in Self::address_commands(column, page as u8 * 8) {
+            command.send(&mut self.iface)?;
+        }
+
+        // Reads don't advance the column address in read-modify-write mode, so the modified byte
+        // is written back to where it was read from
+        Command::ReadModifyWrite.send(&mut self.iface)?;
+
+        let mut byte = [0];
+        self.iface.read_data(&mut byte)?; // Dummy read
+        self.iface.read_data(&mut byte)?;
+
+        let modified = f(byte[0]);
+        if modified != byte[0] {
+            self.iface.send_data(&[modified])?;
+        }
+
+        Command::End.send(&mut self.iface)
+    }
+}
+
+impl<DI, SIZE> DisplayProperties<DI, SIZE>
+where
+    DI: DmaDisplayInterface,
+    SIZE: DisplaySizeType,
+{
+    /// Start sending the part of `buffer` which fits before the draw position has to be
+    /// re-addressed in the background, returning its length. Must be followed by
+    /// [`poll_draw`](#method.poll_draw) with that length before anything else is sent. Fails with
+    /// `NotInitialised` before `init`.
+    pub fn start_draw(&mut self, buffer: &[u8]) -> Result<usize, DI::Error> {
+        self.check_initialised()?;
+
+        let count = self.draw_chunk_len(buffer.len());
+        self.iface.start_data(&buffer[..count])?;
+
+        Ok(count)
+    }
+
+    /// Check whether the transfer started by [`start_draw`](#method.start_draw) finished, and
+    /// advance the draw position past its `count` bytes if it did.
+    pub fn poll_draw(&mut self, count: usize) -> nb::Result<(), DI::Error> {
+        self.iface.poll_data()?;
+
+        if self.advance_draw(count) {
+            self.send_draw_address()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Asynchronous counterparts of the methods sending data to the display, for interfaces
+/// implementing [`AsyncDisplayInterface`]
+#[cfg(feature = "async")]
+impl<DI, SIZE> DisplayProperties<DI, SIZE>
+where
+    DI: AsyncDisplayInterface,
+    SIZE: DisplaySizeType,
+{
+    /// Asynchronous version of [`init_column_mode`](#method.init_column_mode)
+    pub async fn init_column_mode_async(&mut self) -> Result<(), DI::Error> {
+        self.config.validate().map_err(DriverError::Config)?;
+        self.iface.init().await?;
+        self.address_mode = AddressMode::Page;
+
+        for command in self.init_commands() {
+            command.send_async(&mut self.iface).await?;
+        }
+        self.power_state = PowerState::On;
+
+        Ok(())
+    }
+
+    /// Asynchronous version of [`set_draw_area`](#method.set_draw_area)
+    pub async fn set_draw_area_async(
+        &mut self,
+        start: (u8, u8),
+        end: (u8, u8),
+    ) -> Result<(), DI::Error> {
+        self.check_initialised()?;
+        self.start_draw_area(start, end);
+
+        self.send_draw_address_async().await
+    }
+
+    /// Asynchronous version of [`draw`](#method.draw)
+    pub async fn draw_async(&mut self, mut buffer: &[u8]) -> Result<(), DI::Error> {
+        self.check_initialised()?;
+
+        while !buffer.is_empty() {
+            let count = self.draw_chunk_len(buffer.len());
+            self.iface.send_data(&buffer[..count]).await?;
+
+            if self.advance_draw(count) {
+                self.send_draw_address_async().await?;
+            }
+
+            buffer = &buffer[count..];
+        }
+
+        Ok(())
+    }
+
+    /// Asynchronous version of [`set_address_mode`](#method.set_address_mode)
+    pub async fn set_address_mode_async(
+        &mut self,
+        address_mode: AddressMode,
+    ) -> Result<(), DI::Error> {
+        self.address_mode = address_mode;
+
+        Command::AddressMode(address_mode)
+            .send_async(&mut self.iface)
+            .await
+    }
+
+    async fn send_draw_address_async(&mut self) -> Result<(), DI::Error> {
+        for command in self.draw_address_commands() {
+            command.send_async(&mut self.iface).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+impl<DI, SIZE> DisplayProperties<DI, SIZE> {
+    /// Treat the display as initialised without sending the init sequence
+    pub(crate) fn initialised(self) -> Self {
+        Self {
+            power_state: PowerState::On,
+            ..self
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use core::convert::Infallible;
+    use std::vec::Vec;
+
+    use super::{AddressMode, DisplayProperties, Page, PowerState, POWER_OFF_MS, VPP_SETTLE_MS};
+    use crate::{
+        builder::NoOutputPin,
+        config::{ConfigError, PanelConfig, VcomhLevel},
+        displayrotation::DisplayRotation,
+        displaysize::{DisplaySize128x128, DisplaySize128x64},
+        interface::mock::{MockInterface, Transfer},
+        Error,
+    };
+
+    #[test]
+    fn page_mode_addresses_every_page() {
+        let mut props = DisplayProperties::new(
+            MockInterface::default(),
+            DisplaySize128x64,
+            DisplayRotation::Rotate0,
+        )
+        .initialised();
+
+        props.set_draw_area((2, 0), (130, 64)).unwrap();
+        props.draw(&[0xAA; 128 * 64 / 8]).unwrap();
+
+        let pages = props
+            .iface
+            .commands()
+            .iter()
+            .filter(|c| c[0] & 0xF0 == 0xB0)
+            .count();
+        // One address per page, plus one when wrapping back to the start of the area
+        assert_eq!(pages, 8 + 1);
+        assert_eq!(props.iface.data(), [0xAA; 128 * 64 / 8]);
+    }
+
+    #[test]
+    fn vertical_mode_streams_full_height_frame() {
+        let mut props = DisplayProperties::new(
+            MockInterface::default(),
+            DisplaySize128x128,
+            DisplayRotation::Rotate0,
+        )
+        .initialised();
+
+        props.set_address_mode(AddressMode::Vertical).unwrap();
+        props.iface.clear();
+
+        props.set_draw_area((0, 0), (128, 128)).unwrap();
+        props.draw(&[0x55; 128 * 128 / 8]).unwrap();
+
+        let pages = props
+            .iface
+            .commands()
+            .iter()
+            .filter(|c| c[0] & 0xF0 == 0xB0)
+            .count();
+        // The initial address, and one when wrapping back to the start of the area
+        assert_eq!(pages, 2);
+        assert_eq!(props.iface.data(), [0x55; 128 * 128 / 8]);
+    }
+
+    #[test]
+    fn vertical_mode_partial_height_readdresses_columns() {
+        let mut props = DisplayProperties::new(
+            MockInterface::default(),
+            DisplaySize128x128,
+            DisplayRotation::Rotate0,
+        )
+        .initialised();
+
+        props.set_address_mode(AddressMode::Vertical).unwrap();
+        props.iface.clear();
+
+        props.set_draw_area((10, 16), (14, 40)).unwrap();
+        props.draw(&[0xFF; 4 * 3]).unwrap();
+
+        let columns: Vec<_> = props
+            .iface
+            .commands()
+            .iter()
+            .filter(|c| c[0] & 0xF0 == 0x00)
+            .map(|c| c[0])
+            .collect();
+        assert_eq!(columns, [10, 11, 12, 13, 10]);
+    }
+
+    #[test]
+    fn read_page_skips_dummy_byte_and_restores_draw_address() {
+        let mut props = DisplayProperties::new(
+            MockInterface::default(),
+            DisplaySize128x64,
+            DisplayRotation::Rotate0,
+        )
+        .initialised();
+        props.set_draw_area((0, 8), (128, 16)).unwrap();
+        props.iface_mut().clear();
+
+        assert!(!props.read_status().unwrap().is_display_on());
+
+        let mut buf = [0xFF; 16];
+        assert_eq!(props.read_page(Page::Page2, 120, &mut buf).unwrap(), 8);
+        assert_eq!(buf[..8], [0, 1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(buf[8..], [0xFF; 8]);
+
+        assert_eq!(
+            props.iface().transfers,
+            [
+                Transfer::Read(1),
+                // Page 2, column 120
+                Transfer::Commands([0xB2].to_vec()),
+                Transfer::Commands([0x08].to_vec()),
+                Transfer::Commands([0x17].to_vec()),
+                Transfer::Read(1),
+                Transfer::Read(8),
+                // Back to the start of the draw area
+                Transfer::Commands([0xB1].to_vec()),
+                Transfer::Commands([0x00].to_vec()),
+                Transfer::Commands([0x10].to_vec()),
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_below_the_panel_are_rejected() {
+        let mut props = DisplayProperties::new(
+            MockInterface::default(),
+            DisplaySize128x64,
+            DisplayRotation::Rotate0,
+        )
+        .initialised();
+
+        assert_eq!(
+            props.read_page(Page::Page8, 0, &mut [0; 4]),
+            Err(Error::OutOfBounds)
+        );
+        assert_eq!(
+            props.read_modify_write(Page::Page8, 0, |byte| byte | 1),
+            Err(Error::OutOfBounds)
+        );
+        assert!(props.iface.transfers.is_empty());
+    }
+
+    #[test]
+    fn power_sequences_wait_for_supplies() {
+        let mut props = DisplayProperties::new(
+            MockInterface::default(),
+            DisplaySize128x64,
+            DisplayRotation::Rotate0,
+        );
+        let (mut rst, mut vpp): (NoOutputPin<Infallible>, NoOutputPin<Infallible>) =
+            Default::default();
+        let mut delays = Vec::new();
+
+        props
+            .power_on_with(&mut rst, &mut vpp, |ms| delays.push(ms))
+            .unwrap();
+        assert!(props.iface.transfers.is_empty());
+
+        props
+            .power_off_with(&mut rst, &mut vpp, |ms| delays.push(ms))
+            .unwrap();
+        assert_eq!(props.iface.commands(), [[0xAE].to_vec()]);
+        assert_eq!(delays, [1, 1, VPP_SETTLE_MS, POWER_OFF_MS]);
+    }
+
+    #[test]
+    fn sleep_stops_dc_dc_after_display_off() {
+        let mut props = DisplayProperties::new(
+            MockInterface::default(),
+            DisplaySize128x64,
+            DisplayRotation::Rotate0,
+        );
+        props.init_column_mode().unwrap();
+        assert_eq!(props.get_power_state(), PowerState::On);
+        props.iface.clear();
+
+        props.sleep().unwrap();
+        assert_eq!(props.get_power_state(), PowerState::Sleep);
+        props.wake().unwrap();
+        assert_eq!(props.get_power_state(), PowerState::On);
+
+        assert_eq!(
+            props.iface.commands(),
+            [
+                [0xAE].to_vec(),
+                [0xAD, 0x8A].to_vec(),
+                [0xAD, 0x8B].to_vec(),
+                [0xAF].to_vec()
+            ]
+        );
+    }
+
+    #[test]
+    fn driver_errors_are_reported_without_sending() {
+        let mut props = DisplayProperties::new(
+            MockInterface::default(),
+            DisplaySize128x64,
+            DisplayRotation::Rotate0,
+        );
+        let mut buf = [0; 4];
+
+        assert_eq!(props.sleep(), Err(Error::NotInitialised));
+        assert_eq!(props.wake(), Err(Error::NotInitialised));
+        assert_eq!(
+            props.set_draw_area((0, 0), (128, 64)),
+            Err(Error::NotInitialised)
+        );
+        assert_eq!(props.draw(&[0xFF]), Err(Error::NotInitialised));
+        assert_eq!(
+            props.read_page(Page::Page0, 0, &mut buf),
+            Err(Error::NotInitialised)
+        );
+
+        let mut props = props.initialised();
+        assert_eq!(props.set_start_line(128), Err(Error::InvalidArgument));
+        assert_eq!(
+            props.read_page(Page::Page0, 128, &mut buf),
+            Err(Error::OutOfBounds)
+        );
+        assert_eq!(
+            props.read_modify_write(Page::Page0, 128, |byte| byte),
+            Err(Error::OutOfBounds)
+        );
+        assert_eq!(props.iface.transfers, []);
+    }
+
+    #[test]
+    fn init_sends_panel_config() {
+        let config = PanelConfig {
+            oscillator_frequency: 0xF,
+            clock_divide: 2,
+            contrast: 0x2F,
+            precharge_period: 0x2,
+            discharge_period: 0x2,
+            vcomh_level: VcomhLevel::V077,
+            dc_dc: false,
+            alternative_com_pins: Some(true),
+            ..PanelConfig::default()
+        };
+        let mut props = DisplayProperties::new(
+            MockInterface::default(),
+            DisplaySize128x64,
+            DisplayRotation::Rotate0,
+        );
+        props.init_column_mode().unwrap();
+        assert!(props.iface.commands().iter().all(|c| c[0] != 0xDA));
+
+        let mut props = DisplayProperties::new(
+            MockInterface::default(),
+            DisplaySize128x64,
+            DisplayRotation::Rotate0,
+        )
+        .with_panel_config(config);
+
+        props.init_column_mode().unwrap();
+
+        let commands = props.iface.commands();
+        for expected in [
+            [0xD5, 0xF1],
+            [0xAD, 0x8A],
+            [0x81, 0x2F],
+            [0xD9, 0x22],
+            [0xDB, 0x20],
+            [0xDA, 0x12],
+        ] {
+            assert!(commands.contains(&expected.to_vec()));
+        }
+    }
+
+    #[test]
+    fn init_rejects_invalid_panel_config() {
+        for (config, error) in [
+            (
+                PanelConfig {
+                    clock_divide: 0,
+                    ..PanelConfig::default()
+                },
+                ConfigError::ClockDivide,
+            ),
+            (
+                PanelConfig {
+                    precharge_period: 0,
+                    ..PanelConfig::default()
+                },
+                ConfigError::PrechargePeriod,
+            ),
+        ] {
+            let mut props = DisplayProperties::new(
+                MockInterface::default(),
+                DisplaySize128x64,
+                DisplayRotation::Rotate0,
+            )
+            .with_panel_config(config);
+
+            assert_eq!(props.init_column_mode(), Err(Error::Config(error)));
+            assert_eq!(props.iface.transfers, []);
+            assert_eq!(props.get_power_state(), PowerState::Off);
+        }
+    }
 }